@@ -1,107 +1,177 @@
-use std::ops::MulAssign;
-
 use ark_bn254::Fr as ArkFr;
-use halo2curves::{bn256::Fr as Halo2Fr, ff::Field};
-use halo2curves::bn256::Fr as NewHalo2Fr;
+use fast_halo2curves::bn256::Fr as NewHalo2Fr;
+use halo2curves::bn256::Fr as Halo2Fr;
 
-use ark_std::UniformRand;
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use speedy_fields::CIOS;
+use ark_std::rand::{rngs::StdRng, Rng, SeedableRng};
+use criterion::{
+    black_box, criterion_group, criterion_main, measurement::WallTime, BatchSize, BenchmarkGroup, Criterion,
+    Throughput,
+};
+use speedy_fields::{
+    backend::{batch_inverse, Cihs, Dispatched, EdMsm, FieldBackend, Fios, Karatsuba, Sos},
+    barrett::Barrett,
+    prepared::{scale_slice, PreparedMul},
+    CIOS,
+};
 
-fn ark_fr_mul(c: &mut Criterion) {
-    c.bench_function("ArkFr multiplication", |b| {
-        b.iter_batched(
-            || {
-                let mut rng = ark_std::test_rng();
-                let a_op = ArkFr::rand(&mut rng);
-                let b_op = ArkFr::rand(&mut rng);
-                (a_op, b_op)
-            },
-            |(a_op, b_op)| {
-                let c = black_box(a_op) * black_box(b_op);
-                black_box(c)
-            },
-            criterion::BatchSize::SmallInput,
-        )
+const SEED: u64 = 0;
+
+/// Elements per batch benchmark.
+const BATCH: usize = 1024;
+
+/// Calls `$bench::<F>($args)` for every backend, in the order of the
+/// comparison binary's table.
+macro_rules! for_each_backend {
+    ($bench:ident($($arg:expr),*)) => {
+        $bench::<ArkFr>($($arg),*);
+        $bench::<CIOS>($($arg),*);
+        $bench::<EdMsm>($($arg),*);
+        $bench::<Karatsuba>($($arg),*);
+        $bench::<Sos>($($arg),*);
+        $bench::<Fios>($($arg),*);
+        $bench::<Cihs>($($arg),*);
+        $bench::<Dispatched>($($arg),*);
+        $bench::<Barrett>($($arg),*);
+        $bench::<Halo2Fr>($($arg),*);
+        $bench::<NewHalo2Fr>($($arg),*);
+    };
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Mul,
+    Square,
+    Add,
+    Sub,
+    Neg,
+    Inverse,
+    Sqrt,
+}
+
+const OPS: [(Op, &str); 7] = [
+    (Op::Mul, "mul"),
+    (Op::Square, "square"),
+    (Op::Add, "add"),
+    (Op::Sub, "sub"),
+    (Op::Neg, "neg"),
+    (Op::Inverse, "inverse"),
+    (Op::Sqrt, "sqrt"),
+];
+
+fn random<F: FieldBackend>(rng: &mut StdRng) -> F {
+    F::from_limbs(rng.gen())
+}
+
+/// One operation on a fresh pair of operands per iteration. The rng lives
+/// across batches, so operands never repeat.
+fn bench_op<F: FieldBackend>(group: &mut BenchmarkGroup<WallTime>, op: Op) {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let mut operands = move || (random::<F>(&mut rng), random::<F>(&mut rng));
+    group.bench_function(F::NAME, |b| match op {
+        Op::Mul => b.iter_batched(&mut operands, |(x, y)| x.mul(&y), BatchSize::SmallInput),
+        Op::Square => b.iter_batched(&mut operands, |(x, _)| x.square(), BatchSize::SmallInput),
+        Op::Add => b.iter_batched(&mut operands, |(x, y)| x.add(&y), BatchSize::SmallInput),
+        Op::Sub => b.iter_batched(&mut operands, |(x, y)| x.sub(&y), BatchSize::SmallInput),
+        Op::Neg => b.iter_batched(&mut operands, |(x, _)| x.neg(), BatchSize::SmallInput),
+        Op::Inverse => b.iter_batched(&mut operands, |(x, _)| x.inverse(), BatchSize::SmallInput),
+        Op::Sqrt => b.iter_batched(&mut operands, |(x, _)| x.sqrt(), BatchSize::SmallInput),
     });
 }
 
-fn halo2_fr_mul(c: &mut Criterion) {
-    c.bench_function("Halo2Fr OLD multiplication", |b| {
-        b.iter_batched(
-            || {
-                let mut rng = ark_std::test_rng();
-                let a_op = Halo2Fr::random(&mut rng);
-                let b_op = Halo2Fr::random(&mut rng);
-                (a_op, b_op)
-            },
-            |(a_op, b_op)| {
-                let c = black_box(a_op).mul(&black_box(b_op));
-                black_box(c)
-            },
-            criterion::BatchSize::SmallInput,
-        )
+/// One group per operation, one benchmark per backend in each.
+fn field_ops(c: &mut Criterion) {
+    for (op, name) in OPS {
+        let mut group = c.benchmark_group(name);
+        group.throughput(Throughput::Elements(1));
+        for_each_backend!(bench_op(&mut group, op));
+        group.finish();
+    }
+}
+
+fn bench_batch_mul<F: FieldBackend>(group: &mut BenchmarkGroup<WallTime>) {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let pairs: Vec<(F, F)> = (0..BATCH).map(|_| (random(&mut rng), random(&mut rng))).collect();
+    group.bench_function(F::NAME, |b| {
+        b.iter(|| black_box(&pairs).iter().map(|(x, y)| x.mul(y)).collect::<Vec<_>>())
     });
 }
 
-fn halo2_fr_const_mul(c: &mut Criterion) {
-    c.bench_function("Halo2Fr NEW multiplication", |b| {
+fn bench_batch_inverse<F: FieldBackend>(group: &mut BenchmarkGroup<WallTime>) {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let xs: Vec<F> = (0..BATCH).map(|_| random(&mut rng)).collect();
+    group.bench_function(F::NAME, |b| {
         b.iter_batched(
-            || {
-                let mut rng = ark_std::test_rng();
-                let a_op = NewHalo2Fr::random(&mut rng);
-                let b_op = NewHalo2Fr::random(&mut rng);
-                (a_op, b_op)
+            || xs.clone(),
+            |mut xs| {
+                batch_inverse(&mut xs);
+                xs
             },
-            |(a_op, b_op)| {
-                let c = black_box(a_op).mul(&black_box(b_op));
-                black_box(c)
-            },
-            criterion::BatchSize::SmallInput,
+            BatchSize::SmallInput,
         )
     });
 }
 
-fn cios_mul(c: &mut Criterion) {
-    c.bench_function("CIOS multiplication", |b| {
-        b.iter_batched(
-            || {
-                let a_op = CIOS::rand();
-                let b_op = CIOS::rand();
-                (a_op, b_op)
-            },
-            |(a_op, b_op)| {
-                let c = black_box(a_op).mul(&black_box(b_op));
-                black_box(c)
-            },
-            criterion::BatchSize::SmallInput,
-        )
+/// Slices of `BATCH` elements; throughput is per element.
+fn batch_ops(c: &mut Criterion) {
+    let mut group = c.benchmark_group("batch mul");
+    group.throughput(Throughput::Elements(BATCH as u64));
+    for_each_backend!(bench_batch_mul(&mut group));
+    group.finish();
+
+    let mut group = c.benchmark_group("batch inverse");
+    group.throughput(Throughput::Elements(BATCH as u64));
+    for_each_backend!(bench_batch_inverse(&mut group));
+    group.finish();
+}
+
+fn cios_small_mul(c: &mut Criterion) {
+    let k = 0x1234_5678_9abc_def0u64;
+    let k_mont = CIOS::from_canonical([k, 0, 0, 0]);
+    let mut group = c.benchmark_group("CIOS small multiplier");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("mul_edmsm by u64 as full product", |b| {
+        b.iter_batched(CIOS::rand, |a_op| a_op.mul_edmsm(&black_box(k_mont)), BatchSize::SmallInput)
+    });
+    group.bench_function("mul_u64", |b| {
+        b.iter_batched(CIOS::rand, |a_op| a_op.mul_u64(black_box(k)), BatchSize::SmallInput)
+    });
+    group.bench_function("mul_by_small_const::<3>", |b| {
+        b.iter_batched(CIOS::rand, |a_op| a_op.mul_by_small_const::<3>(), BatchSize::SmallInput)
     });
+    group.finish();
 }
 
-fn cios_edmsm_mul(c: &mut Criterion) {
-    c.bench_function("CIOS multiplication", |b| {
+fn prepared_mul(c: &mut Criterion) {
+    let constant = CIOS::rand();
+    let prepared = PreparedMul::new(&constant);
+    let elems: Vec<CIOS> = (0..BATCH).map(|_| CIOS::rand()).collect();
+
+    let mut group = c.benchmark_group("scale by a constant");
+    group.throughput(Throughput::Elements(BATCH as u64));
+    group.bench_function("repeated mul_edmsm", |b| {
         b.iter_batched(
-            || {
-                let a_op = CIOS::rand();
-                let b_op = CIOS::rand();
-                (a_op, b_op)
+            || elems.clone(),
+            |mut elems| {
+                for x in elems.iter_mut() {
+                    *x = x.mul_edmsm(black_box(&constant));
+                }
+                elems
             },
-            |(a_op, b_op)| {
-                let c = black_box(a_op).mul_edmsm(&black_box(b_op));
-                black_box(c)
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("PreparedMul", |b| {
+        b.iter_batched(
+            || elems.clone(),
+            |mut elems| {
+                scale_slice(&mut elems, black_box(&prepared));
+                elems
             },
-            criterion::BatchSize::SmallInput,
+            BatchSize::SmallInput,
         )
     });
+    group.finish();
 }
 
-criterion_group!(
-    benches,
-    ark_fr_mul,
-    halo2_fr_mul,
-    halo2_fr_const_mul,
-    cios_mul,
-    cios_edmsm_mul
-);
+criterion_group!(benches, field_ops, batch_ops, cios_small_mul, prepared_mul);
 criterion_main!(benches);
@@ -0,0 +1,42 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use speedy_fields::{soa::FrVec, CIOS};
+
+const LEN: usize = 1 << 16;
+
+fn operands() -> (Vec<(CIOS, CIOS)>, FrVec, FrVec) {
+    let pairs: Vec<(CIOS, CIOS)> = (0..LEN).map(|_| (CIOS::rand(), CIOS::rand())).collect();
+    let lhs: Vec<CIOS> = pairs.iter().map(|(a, _)| *a).collect();
+    let rhs: Vec<CIOS> = pairs.iter().map(|(_, b)| *b).collect();
+    (pairs, FrVec::from(lhs), FrVec::from(rhs))
+}
+
+fn aos_vs_soa(c: &mut Criterion) {
+    let (pairs, lhs, rhs) = operands();
+    let mut group = c.benchmark_group("AoS vs SoA");
+    group.throughput(Throughput::Elements(LEN as u64));
+
+    group.bench_function("AoS add", |b| {
+        b.iter(|| black_box(&pairs).iter().map(|(x, y)| x.add(y)).collect::<Vec<_>>())
+    });
+    group.bench_function("SoA add", |b| b.iter(|| black_box(&lhs).add(black_box(&rhs))));
+
+    group.bench_function("AoS mul", |b| {
+        b.iter(|| black_box(&pairs).iter().map(|(x, y)| x.mul_edmsm(y)).collect::<Vec<_>>())
+    });
+    group.bench_function("SoA mul", |b| b.iter(|| black_box(&lhs).mul(black_box(&rhs))));
+
+    group.bench_function("AoS inner product", |b| {
+        b.iter(|| {
+            black_box(&pairs)
+                .iter()
+                .fold(CIOS::ZERO, |acc, (x, y)| acc.add(&x.mul_edmsm(y)))
+        })
+    });
+    group.bench_function("SoA inner product", |b| {
+        b.iter(|| black_box(&lhs).inner_product(black_box(&rhs)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, aos_vs_soa);
+criterion_main!(benches);
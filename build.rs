@@ -0,0 +1,26 @@
+/// Records the toolchain and commit the binaries were built from, for the
+/// host metadata in the benchmark's JSON/CSV output.
+use std::process::Command;
+
+fn main() {
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    println!("cargo:rustc-env=SPEEDY_FIELDS_RUSTC_VERSION={}", output_of(Command::new(rustc).arg("--version")));
+    println!(
+        "cargo:rustc-env=SPEEDY_FIELDS_GIT_COMMIT={}",
+        output_of(Command::new("git").args(["describe", "--always", "--dirty", "--abbrev=40"]))
+    );
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/index");
+}
+
+/// Trimmed stdout, or "unknown" when the command is missing or fails.
+fn output_of(command: &mut Command) -> String {
+    command
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|stdout| stdout.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
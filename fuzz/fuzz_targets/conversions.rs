@@ -0,0 +1,33 @@
+//! Conversions in and out of Montgomery form: `from_canonical`,
+//! `to_canonical` and `to_bytes` accept any 256-bit value, `redc` any
+//! 512-bit value below `r * 2^256`.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use speedy_fields::CIOS;
+use speedy_fields_fuzz::{big, limbs, modulus, montgomery_reduce, radix};
+
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 64 {
+        return;
+    }
+    let a: [u64; 4] = limbs(&data[..32]);
+    let (big_a, r) = (big(&a), modulus());
+
+    let mont = CIOS::from_canonical(a);
+    assert_eq!(big(mont.limbs()), &big_a * radix() % &r, "from_canonical({a:x?})");
+    assert_eq!(big(&mont.to_canonical()), &big_a % &r, "from_canonical({a:x?}).to_canonical()");
+
+    let raw = CIOS::from_raw_unchecked(a);
+    let canonical = montgomery_reduce(&big_a);
+    assert_eq!(big(&raw.to_canonical()), canonical, "to_canonical({a:x?})");
+    let mut bytes = canonical.to_bytes_le();
+    bytes.resize(32, 0);
+    assert_eq!(raw.to_bytes().as_slice(), bytes.as_slice(), "to_bytes({a:x?})");
+
+    let t: [u64; 8] = limbs(&data[..64]);
+    let big_t = big(&t);
+    if big_t < &r * radix() {
+        assert_eq!(big(CIOS::redc(t).limbs()), montgomery_reduce(&big_t), "redc({t:x?})");
+    }
+});
@@ -0,0 +1,46 @@
+//! Every Montgomery multiplier against `a * b * R^{-1} mod r`, for operands
+//! inside each variant's documented input range.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use num_bigint::BigUint;
+use speedy_fields::CIOS;
+use speedy_fields_fuzz::{big, limbs, modulus, montgomery_reduce, radix};
+
+type MulFn = fn(&CIOS, &CIOS) -> CIOS;
+
+/// Variants whose only requirement is `a * b < r * 2^256`, i.e. that the
+/// final conditional subtraction suffices.
+const PRODUCT_BOUND_VARIANTS: [(&str, MulFn); 5] = [
+    ("mul", CIOS::mul),
+    ("mul_sos", CIOS::mul_sos),
+    ("mul_fios", CIOS::mul_fios),
+    ("mul_cihs", CIOS::mul_cihs),
+    ("mul_karatsuba", CIOS::mul_karatsuba),
+];
+
+fn check(name: &str, got: &CIOS, expected: &BigUint, a: &CIOS, b: &CIOS) {
+    assert_eq!(&big(got.limbs()), expected, "{name}({a:x?}, {b:x?})");
+}
+
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 64 {
+        return;
+    }
+    let (a, b) = (CIOS::from_raw_unchecked(limbs(&data[..32])), CIOS::from_raw_unchecked(limbs(&data[32..64])));
+    let (big_a, big_b) = (big(a.limbs()), big(b.limbs()));
+    let r = modulus();
+    let expected = montgomery_reduce(&(&big_a * &big_b));
+
+    if &big_a * &big_b < &r * radix() {
+        for (name, mul) in PRODUCT_BOUND_VARIANTS {
+            check(name, &mul(&a, &b), &expected, &a, &b);
+        }
+    }
+
+    // The EdMSM carry skip additionally needs both operands below 2r.
+    let two_r = &r * 2u8;
+    if big_a < two_r && big_b < two_r {
+        check("mul_edmsm", &a.mul_edmsm(&b), &expected, &a, &b);
+    }
+});
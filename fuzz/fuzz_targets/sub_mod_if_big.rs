@@ -0,0 +1,23 @@
+//! `sub_mod_if_big` accepts any 256-bit value and subtracts r at most once,
+//! so its output is only reduced for inputs below 2r.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use speedy_fields::CIOS;
+use speedy_fields_fuzz::{big, limbs, modulus};
+
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 32 {
+        return;
+    }
+    let a: [u64; 4] = limbs(&data[..32]);
+    let mut t = a;
+    CIOS::sub_mod_if_big(&mut t);
+
+    let (big_a, r) = (big(&a), modulus());
+    let expected = if big_a >= r { &big_a - &r } else { big_a.clone() };
+    assert_eq!(big(&t), expected, "sub_mod_if_big({a:x?})");
+    if big_a < &r * 2u8 {
+        assert!(big(&t) < r, "sub_mod_if_big({a:x?}) is not reduced");
+    }
+});
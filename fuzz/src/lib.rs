@@ -0,0 +1,45 @@
+/// Plain `num-bigint` reference arithmetic for the fuzz targets, and helpers
+/// to carve limbs out of the fuzzer's byte input.
+use std::sync::OnceLock;
+
+use num_bigint::BigUint;
+
+/// `r`, little-endian limbs.
+pub const MODULUS: [u64; 4] = [0x43e1f593f0000001, 0x2833e84879b97091, 0xb85045b68181585d, 0x30644e72e131a029];
+
+/// Little-endian limbs from exactly `8 * N` bytes.
+pub fn limbs<const N: usize>(bytes: &[u8]) -> [u64; N] {
+    let mut limbs = [0u64; N];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+        *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+    limbs
+}
+
+pub fn big(limbs: &[u64]) -> BigUint {
+    let digits: Vec<u32> = limbs.iter().flat_map(|l| [*l as u32, (l >> 32) as u32]).collect();
+    BigUint::from_slice(&digits)
+}
+
+pub fn modulus() -> BigUint {
+    big(&MODULUS)
+}
+
+/// `R = 2^256`, the Montgomery radix.
+pub fn radix() -> BigUint {
+    BigUint::from(1u8) << 256
+}
+
+/// `R^{-1} mod r`, via Fermat. Computed once per process.
+pub fn radix_inv() -> &'static BigUint {
+    static RADIX_INV: OnceLock<BigUint> = OnceLock::new();
+    RADIX_INV.get_or_init(|| {
+        let r = modulus();
+        radix().modpow(&(&r - 2u8), &r)
+    })
+}
+
+/// `x * R^{-1} mod r`, what Montgomery reduction must return.
+pub fn montgomery_reduce(x: &BigUint) -> BigUint {
+    x * radix_inv() % modulus()
+}
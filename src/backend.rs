@@ -0,0 +1,361 @@
+/// Common interface over every Bn254 Fr implementation the benchmark binary
+/// compares: the external arkworks and halo2curves fields, and each kernel
+/// of this crate. Timing code is written once against `FieldBackend`, so a
+/// new contender only needs an impl here and one line in `src/main.rs`.
+///
+/// The arithmetic itself is `FieldOps`; a backend adds the constructor the
+/// benchmarks draw operands through, and a square root.
+use ark_bn254::Fr as ArkFr;
+use ark_ff::{BigInt, PrimeField};
+use fast_halo2curves::bn256::Fr as NewHalo2Fr;
+use halo2curves::bn256::Fr as Halo2Fr;
+
+use crate::{
+    barrett::Barrett,
+    dispatch,
+    field::{pow, FieldOps},
+    CIOS,
+};
+
+/// `r - 1 = 2^S * T` with `T` odd.
+const TWO_ADICITY: u32 = 28;
+
+/// `(T - 1) / 2`
+const T_MINUS_ONE_DIV_TWO: [u64; 4] = [0xcdcb848a1f0fac9f, 0x0c0ac2e9419f4243, 0x098d014dc2822db4, 0x0000000183227397];
+
+/// `5^T`, a primitive 2^S-th root of unity (5 is a non-residue mod r).
+/// `0x2a3c09f0a58a7e8500e0a7eb8ef62abc402d111e41112ed49bd61b6e725b19f0`
+const ROOT_OF_UNITY: [u64; 4] = [0x9bd61b6e725b19f0, 0x402d111e41112ed4, 0x00e0a7eb8ef62abc, 0x2a3c09f0a58a7e85];
+
+pub trait FieldBackend: FieldOps + Send + Sync {
+    /// Reads arbitrary limbs as a little-endian integer and reduces it mod r,
+    /// which is what every backend's own constructor does.
+    fn from_limbs(limbs: [u64; 4]) -> Self;
+
+    /// One of the two square roots, `None` for non-residues. Defaults to
+    /// Tonelli-Shanks on top of this backend's `mul`. Variable time.
+    fn sqrt(&self) -> Option<Self> {
+        let one = Self::one();
+        if *self == Self::zero() {
+            return Some(*self);
+        }
+        // x^((T + 1) / 2) is a root of x * b with b = x^T in the 2^S-torsion;
+        // each step moves b into a smaller subgroup until it reaches 1.
+        let w = pow(self, &T_MINUS_ONE_DIV_TWO);
+        let mut root = self.mul(&w);
+        let mut b = root.mul(&w);
+        let mut z = Self::from_canonical(ROOT_OF_UNITY);
+        let mut v = TWO_ADICITY;
+        while b != one {
+            let mut k = 0;
+            let mut b2k = b;
+            while b2k != one {
+                b2k = b2k.square();
+                k += 1;
+                if k == v {
+                    return None;
+                }
+            }
+            let mut w = z;
+            for _ in 0..v - k - 1 {
+                w = w.square();
+            }
+            z = w.square();
+            b = b.mul(&z);
+            root = root.mul(&w);
+            v = k;
+        }
+        Some(root)
+    }
+}
+
+/// Inverts every element in place with a single inversion and `3(n - 1)`
+/// multiplications (Montgomery's trick). Zeros are left as zero.
+pub fn batch_inverse<F: FieldOps>(xs: &mut [F]) {
+    let zero = F::zero();
+    // prefixes[i] is the product of the nonzero elements before i.
+    let mut prefixes = Vec::with_capacity(xs.len());
+    let mut acc = F::one();
+    for x in xs.iter() {
+        prefixes.push(acc);
+        if *x != zero {
+            acc = acc.mul(x);
+        }
+    }
+    let mut inv = acc.inverse().expect("a product of nonzero elements is nonzero");
+    for (x, prefix) in xs.iter_mut().zip(prefixes).rev() {
+        if *x != zero {
+            let next = inv.mul(x);
+            *x = inv.mul(&prefix);
+            inv = next;
+        }
+    }
+}
+
+/// Canonical limbs of a 32-byte little-endian `PrimeField::Repr`.
+fn limbs_from_le_bytes(bytes: &[u8]) -> [u64; 4] {
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+        *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+    limbs
+}
+
+impl FieldOps for ArkFr {
+    const NAME: &'static str = "ArkFr";
+
+    fn zero() -> Self {
+        ark_ff::Zero::zero()
+    }
+
+    fn one() -> Self {
+        ark_ff::One::one()
+    }
+
+    fn from_canonical(limbs: [u64; 4]) -> Self {
+        ArkFr::new(BigInt::new(limbs))
+    }
+
+    fn to_canonical(&self) -> [u64; 4] {
+        self.into_bigint().0
+    }
+
+    #[inline(always)]
+    fn mul(&self, rhs: &Self) -> Self {
+        *self * rhs
+    }
+
+    #[inline(always)]
+    fn square(&self) -> Self {
+        ark_ff::Field::square(self)
+    }
+
+    #[inline(always)]
+    fn add(&self, rhs: &Self) -> Self {
+        *self + rhs
+    }
+
+    #[inline(always)]
+    fn sub(&self, rhs: &Self) -> Self {
+        *self - rhs
+    }
+
+    #[inline(always)]
+    fn neg(&self) -> Self {
+        -*self
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        ark_ff::Field::inverse(self)
+    }
+}
+
+impl FieldBackend for ArkFr {
+    fn from_limbs(limbs: [u64; 4]) -> Self {
+        ArkFr::new(BigInt::new(limbs))
+    }
+
+    fn sqrt(&self) -> Option<Self> {
+        ark_ff::Field::sqrt(self)
+    }
+}
+
+impl FieldOps for Halo2Fr {
+    const NAME: &'static str = "OLD Halo2Fr";
+
+    fn zero() -> Self {
+        <Halo2Fr as halo2curves::ff::Field>::ZERO
+    }
+
+    fn one() -> Self {
+        <Halo2Fr as halo2curves::ff::Field>::ONE
+    }
+
+    fn from_canonical(limbs: [u64; 4]) -> Self {
+        Halo2Fr::from_raw(limbs)
+    }
+
+    fn to_canonical(&self) -> [u64; 4] {
+        limbs_from_le_bytes(halo2curves::ff::PrimeField::to_repr(self).as_ref())
+    }
+
+    #[inline(always)]
+    fn mul(&self, rhs: &Self) -> Self {
+        *self * rhs
+    }
+
+    #[inline(always)]
+    fn square(&self) -> Self {
+        halo2curves::ff::Field::square(self)
+    }
+
+    #[inline(always)]
+    fn add(&self, rhs: &Self) -> Self {
+        *self + rhs
+    }
+
+    #[inline(always)]
+    fn sub(&self, rhs: &Self) -> Self {
+        *self - rhs
+    }
+
+    #[inline(always)]
+    fn neg(&self) -> Self {
+        -*self
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        halo2curves::ff::Field::invert(self).into()
+    }
+}
+
+impl FieldBackend for Halo2Fr {
+    fn from_limbs(limbs: [u64; 4]) -> Self {
+        Halo2Fr::from_raw(limbs)
+    }
+
+    fn sqrt(&self) -> Option<Self> {
+        halo2curves::ff::Field::sqrt(self).into()
+    }
+}
+
+// The fork may pin a different `ff`, hence the fully qualified calls.
+impl FieldOps for NewHalo2Fr {
+    const NAME: &'static str = "NEW Halo2Fr";
+
+    fn zero() -> Self {
+        <NewHalo2Fr as fast_halo2curves::ff::Field>::ZERO
+    }
+
+    fn one() -> Self {
+        <NewHalo2Fr as fast_halo2curves::ff::Field>::ONE
+    }
+
+    fn from_canonical(limbs: [u64; 4]) -> Self {
+        NewHalo2Fr::from_raw(limbs)
+    }
+
+    fn to_canonical(&self) -> [u64; 4] {
+        limbs_from_le_bytes(fast_halo2curves::ff::PrimeField::to_repr(self).as_ref())
+    }
+
+    #[inline(always)]
+    fn mul(&self, rhs: &Self) -> Self {
+        *self * rhs
+    }
+
+    #[inline(always)]
+    fn square(&self) -> Self {
+        fast_halo2curves::ff::Field::square(self)
+    }
+
+    #[inline(always)]
+    fn add(&self, rhs: &Self) -> Self {
+        *self + rhs
+    }
+
+    #[inline(always)]
+    fn sub(&self, rhs: &Self) -> Self {
+        *self - rhs
+    }
+
+    #[inline(always)]
+    fn neg(&self) -> Self {
+        -*self
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        fast_halo2curves::ff::Field::invert(self).into()
+    }
+}
+
+impl FieldBackend for NewHalo2Fr {
+    fn from_limbs(limbs: [u64; 4]) -> Self {
+        NewHalo2Fr::from_raw(limbs)
+    }
+
+    fn sqrt(&self) -> Option<Self> {
+        fast_halo2curves::ff::Field::sqrt(self).into()
+    }
+}
+
+impl FieldBackend for CIOS {
+    fn from_limbs(limbs: [u64; 4]) -> Self {
+        CIOS::from_canonical(limbs)
+    }
+}
+
+/// A `CIOS` whose multiplication goes through `$mul`; everything else is
+/// `CIOS`'s own.
+macro_rules! cios_kernel {
+    ($(#[$doc:meta])* $backend:ident, $name:literal, |$a:ident, $b:ident| $mul:expr) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub struct $backend(pub CIOS);
+
+        impl FieldOps for $backend {
+            const NAME: &'static str = $name;
+
+            fn zero() -> Self {
+                Self(CIOS::ZERO)
+            }
+
+            fn one() -> Self {
+                Self(CIOS::ONE)
+            }
+
+            fn from_canonical(limbs: [u64; 4]) -> Self {
+                Self(CIOS::from_canonical(limbs))
+            }
+
+            fn to_canonical(&self) -> [u64; 4] {
+                self.0.to_canonical()
+            }
+
+            #[inline(always)]
+            fn mul(&self, rhs: &Self) -> Self {
+                let ($a, $b) = (&self.0, &rhs.0);
+                Self($mul)
+            }
+
+            #[inline(always)]
+            fn add(&self, rhs: &Self) -> Self {
+                Self(self.0.add(&rhs.0))
+            }
+
+            #[inline(always)]
+            fn sub(&self, rhs: &Self) -> Self {
+                Self(self.0.sub(&rhs.0))
+            }
+
+            #[inline(always)]
+            fn neg(&self) -> Self {
+                Self(self.0.neg())
+            }
+        }
+
+        impl FieldBackend for $backend {
+            fn from_limbs(limbs: [u64; 4]) -> Self {
+                Self(CIOS::from_canonical(limbs))
+            }
+        }
+    };
+}
+
+cios_kernel!(EdMsm, "CIOS EdMSM", |a, b| a.mul_edmsm(b));
+cios_kernel!(Karatsuba, "CIOS Karatsuba", |a, b| a.mul_karatsuba(b));
+cios_kernel!(Sos, "CIOS SOS", |a, b| a.mul_sos(b));
+cios_kernel!(Fios, "CIOS FIOS", |a, b| a.mul_fios(b));
+cios_kernel!(Cihs, "CIOS CIHS", |a, b| a.mul_cihs(b));
+cios_kernel!(
+    /// `dispatch::mul`, i.e. whichever kernel was selected at startup.
+    Dispatched,
+    "CIOS dispatch",
+    |a, b| dispatch::mul(a, b)
+);
+
+impl FieldBackend for Barrett {
+    fn from_limbs(limbs: [u64; 4]) -> Self {
+        Barrett::from_cios(&CIOS::from_canonical(limbs))
+    }
+}
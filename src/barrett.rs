@@ -0,0 +1,124 @@
+/// Bn254 Fr in canonical (non-Montgomery) form, reduced with Barrett's
+/// algorithm (HAC 14.42, b = 2^64, k = 4). Values are stored exactly as they
+/// are serialised, so there is no conversion at I/O boundaries, at the price
+/// of a more expensive reduction per multiplication.
+use ark_std::rand;
+
+use crate::{add_with_carry, bigint_greater_eq, mac, sbb, sub_with_borrow, CIOS, MODULUS};
+
+/// `MU = floor(2^512 / r)`
+/// `0x54a47462623a04a7ab074a58680730147144852009e880ae620703a6be1de9259`
+const MU: [u64; 5] = [
+    0x20703a6be1de9259,
+    0x144852009e880ae6,
+    0xb074a58680730147,
+    0x4a47462623a04a7a,
+    0x0000000000000005,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Barrett(pub [u64; 4]);
+
+impl Barrett {
+    #[inline(always)]
+    pub fn mul(&self, rhs: &Self) -> Self {
+        let mut x = [0u64; 8];
+        for i in 0..4 {
+            let mut c = 0u64;
+            for j in 0..4 {
+                (x[i + j], c) = mac(x[i + j], self.0[i], rhs.0[j], c);
+            }
+            x[i + 4] = c;
+        }
+        Self::reduce(&x)
+    }
+
+    /// Barrett reduction of a product of two reduced elements (`x < r^2`).
+    #[inline(always)]
+    pub fn reduce(x: &[u64; 8]) -> Self {
+        // q1 = x / b^3, q3 = q1 * MU / b^5
+        let q1 = [x[3], x[4], x[5], x[6], x[7]];
+        let mut q2 = [0u64; 10];
+        for i in 0..5 {
+            let mut c = 0u64;
+            for j in 0..5 {
+                (q2[i + j], c) = mac(q2[i + j], q1[i], MU[j], c);
+            }
+            q2[i + 5] = c;
+        }
+        let q3 = [q2[5], q2[6], q2[7], q2[8], q2[9]];
+
+        // r = (x - q3 * r) mod b^5, which is below 3r
+        let mut qr = [0u64; 5];
+        for (i, q) in q3.iter().enumerate() {
+            let mut c = 0u64;
+            for j in 0..4.min(5 - i) {
+                (qr[i + j], c) = mac(qr[i + j], *q, MODULUS[j], c);
+            }
+            if i == 0 {
+                qr[4] = c;
+            }
+        }
+        let mut r = [x[0], x[1], x[2], x[3], x[4]];
+        let mut borrow = 0u64;
+        for (limb, q) in r.iter_mut().zip(qr) {
+            borrow = sbb(limb, q, borrow);
+        }
+        debug_assert_eq!(r[4], 0, "Barrett remainder must fit in four limbs");
+
+        let mut res = [r[0], r[1], r[2], r[3]];
+        Self::sub_mod_if_big(&mut res);
+        Self::sub_mod_if_big(&mut res);
+        Self(res)
+    }
+
+    #[inline(always)]
+    pub fn add(&self, rhs: &Self) -> Self {
+        let mut t = self.0;
+        add_with_carry(&mut t, &rhs.0);
+        Self::sub_mod_if_big(&mut t);
+        Self(t)
+    }
+
+    #[inline(always)]
+    pub fn sub(&self, rhs: &Self) -> Self {
+        let mut t = self.0;
+        if sub_with_borrow(&mut t, &rhs.0) {
+            add_with_carry(&mut t, &MODULUS);
+        }
+        Self(t)
+    }
+
+    /// Random reduced element, since `reduce` relies on its input being
+    /// below r^2.
+    pub fn rand() -> Self {
+        use rand::Rng;
+        let mut rng = rand::thread_rng();
+        let mut t = [rng.gen(), rng.gen(), rng.gen(), rng.gen()];
+        while bigint_greater_eq(&t, &MODULUS) {
+            sub_with_borrow(&mut t, &MODULUS);
+        }
+        Self(t)
+    }
+
+    #[inline(always)]
+    pub fn sub_mod_if_big(a: &mut [u64; 4]) {
+        CIOS::sub_mod_if_big(a)
+    }
+
+    /// Little-endian canonical encoding; free for this representation.
+    #[inline(always)]
+    pub fn to_bytes(&self) -> [u8; 32] {
+        crate::limbs_to_bytes(&self.0)
+    }
+
+    #[inline(always)]
+    pub fn to_cios(&self) -> CIOS {
+        CIOS::from_canonical(self.0)
+    }
+
+    #[inline(always)]
+    pub fn from_cios(a: &CIOS) -> Self {
+        Self(a.to_canonical())
+    }
+}
@@ -0,0 +1,160 @@
+/// Static codegen metrics for every multiplier, read from this binary.
+///
+/// Each multiplier gets a `#[no_mangle]`, never-inlined wrapper below, so
+/// its optimised body has a symbol of its own. The binary then disassembles
+/// itself with `objdump` and counts, per wrapper, instructions, multiplies,
+/// carry-chain adds and subtracts, stack accesses (spills and reloads),
+/// pushes/pops of callee-saved registers, and calls that were not inlined.
+///
+/// `cargo run --release --bin asm -- [--objdump PATH] [--function NAME] [--listing]`
+use std::{hint::black_box, path::Path, process::Command};
+
+use clap::Parser;
+use speedy_fields::{barrett::Barrett, ct::CtFr, dispatch, lazy::LazyFr, CIOS};
+
+macro_rules! wrappers {
+    ($($(#[$attr:meta])* $symbol:ident: $name:literal, $ty:ty, |$a:ident, $b:ident| $body:expr;)*) => {
+        $(
+            $(#[$attr])*
+            #[no_mangle]
+            #[inline(never)]
+            pub fn $symbol($a: &$ty, $b: &$ty) -> $ty {
+                $body
+            }
+        )*
+
+        /// (function, wrapper symbol) for every wrapper built on this target.
+        /// Pushed one by one because `vec![]` elements cannot carry `#[cfg]`.
+        #[allow(clippy::vec_init_then_push)]
+        fn wrappers() -> Vec<(&'static str, &'static str)> {
+            let mut wrappers = Vec::new();
+            $($(#[$attr])* wrappers.push(($name, stringify!($symbol)));)*
+            wrappers
+        }
+
+        /// Takes every wrapper's address so the linker keeps them all.
+        fn keep_wrappers() {
+            $($(#[$attr])* black_box($symbol as fn(&$ty, &$ty) -> $ty);)*
+        }
+    };
+}
+
+wrappers! {
+    speedy_fields_cios_mul: "CIOS::mul", CIOS, |a, b| a.mul(b);
+    speedy_fields_cios_mul_edmsm: "CIOS::mul_edmsm", CIOS, |a, b| a.mul_edmsm(b);
+    speedy_fields_cios_mul_sos: "CIOS::mul_sos", CIOS, |a, b| a.mul_sos(b);
+    speedy_fields_cios_mul_fios: "CIOS::mul_fios", CIOS, |a, b| a.mul_fios(b);
+    speedy_fields_cios_mul_cihs: "CIOS::mul_cihs", CIOS, |a, b| a.mul_cihs(b);
+    speedy_fields_cios_mul_karatsuba: "CIOS::mul_karatsuba", CIOS, |a, b| a.mul_karatsuba(b);
+    speedy_fields_cios_square: "CIOS::square", CIOS, |a, _b| a.square();
+    // Only the load of the cached kernel pointer and the indirect call; the
+    // kernels themselves have rows of their own.
+    speedy_fields_dispatch_mul: "dispatch::mul (call)", CIOS, |a, b| dispatch::mul(a, b);
+    // Disassembled, never called, so the CPU check does not matter.
+    #[cfg(target_arch = "x86_64")]
+    speedy_fields_dispatch_mul_adx: "Kernel::Adx", CIOS, |a, b| unsafe { dispatch::mul_adx_unchecked(a, b) };
+    speedy_fields_barrett_mul: "Barrett::mul", Barrett, |a, b| a.mul(b);
+    speedy_fields_lazy_mul: "LazyFr::mul", LazyFr, |a, b| a.mul(b);
+    speedy_fields_ct_mul: "CtFr::mul", CtFr, |a, b| a.mul(b);
+}
+
+/// Static codegen metrics for every multiplier, read from this binary.
+#[derive(Debug, Parser)]
+struct Args {
+    /// objdump to disassemble with, e.g. `llvm-objdump`.
+    #[arg(long, default_value = "objdump")]
+    objdump: String,
+
+    /// Report only this function, e.g. `CIOS::mul_edmsm`.
+    #[arg(long)]
+    function: Option<String>,
+
+    /// Also print each function's instructions.
+    #[arg(long)]
+    listing: bool,
+}
+
+#[derive(Debug, Default)]
+struct Metrics {
+    instructions: usize,
+    multiplies: usize,
+    /// Add-with-carry and subtract-with-borrow.
+    carries: usize,
+    stack: usize,
+    push_pop: usize,
+    calls: usize,
+}
+
+impl Metrics {
+    /// Classifies one instruction, given as `mnemonic operands`.
+    fn count(&mut self, instruction: &str) {
+        let (mnemonic, operands) = instruction.split_once(char::is_whitespace).unwrap_or((instruction, ""));
+        self.instructions += 1;
+        if cfg!(target_arch = "aarch64") {
+            self.multiplies += ["mul", "umulh", "umull", "madd"].contains(&mnemonic) as usize;
+            self.carries += ["adc", "adcs", "sbc", "sbcs"].contains(&mnemonic) as usize;
+            self.stack += operands.contains("[sp") as usize;
+            self.calls += (mnemonic == "bl" || mnemonic == "blr") as usize;
+        } else {
+            self.multiplies += (mnemonic.starts_with("mul") || mnemonic.starts_with("imul")) as usize;
+            self.carries += ["adc", "adox", "sbb"].iter().any(|m| mnemonic.starts_with(m)) as usize;
+            if mnemonic.starts_with("push") || mnemonic.starts_with("pop") {
+                self.push_pop += 1;
+            } else {
+                self.stack += (operands.contains("(%rsp") || operands.contains("(%rbp")) as usize;
+            }
+            self.calls += mnemonic.starts_with("call") as usize;
+        }
+    }
+}
+
+/// The instructions of `symbol` in `exe`, as `mnemonic operands`.
+fn disassemble(objdump: &str, exe: &Path, symbol: &str) -> Vec<String> {
+    let output = Command::new(objdump)
+        .args(["-d", "--no-show-raw-insn", &format!("--disassemble={symbol}")])
+        .arg(exe)
+        .output()
+        .unwrap_or_else(|e| panic!("running {objdump}: {e}"));
+    assert!(output.status.success(), "{objdump} failed: {}", String::from_utf8_lossy(&output.stderr));
+    // Instruction lines look like "  4011a6:\tmov    %rdi,%rax".
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (address, instruction) = line.split_once(":\t")?;
+            let address = address.trim();
+            (!address.is_empty() && address.chars().all(|c| c.is_ascii_hexdigit()))
+                .then(|| instruction.split_whitespace().collect::<Vec<_>>().join(" "))
+        })
+        .collect()
+}
+
+fn main() {
+    let args = Args::parse();
+    if cfg!(debug_assertions) {
+        eprintln!("warning: this is a debug build; use --release to inspect optimised code");
+    }
+
+    keep_wrappers();
+    let exe = std::env::current_exe().expect("locating this binary");
+    println!(
+        "{:<22} {:>7} {:>5} {:>7} {:>6} {:>9} {:>6}",
+        "function", "instrs", "mul", "adc/sbb", "stack", "push/pop", "calls"
+    );
+    for (name, symbol) in wrappers().into_iter().filter(|(name, _)| args.function.as_deref().is_none_or(|only| *name == only)) {
+        let instructions = disassemble(&args.objdump, &exe, symbol);
+        assert!(!instructions.is_empty(), "{symbol} not found in {}", exe.display());
+        let mut metrics = Metrics::default();
+        for instruction in &instructions {
+            metrics.count(instruction);
+        }
+        println!(
+            "{:<22} {:>7} {:>5} {:>7} {:>6} {:>9} {:>6}",
+            name, metrics.instructions, metrics.multiplies, metrics.carries, metrics.stack, metrics.push_pop, metrics.calls
+        );
+        if args.listing {
+            for instruction in &instructions {
+                println!("    {instruction}");
+            }
+        }
+    }
+}
@@ -0,0 +1,233 @@
+/// dudect-style timing leakage check (https://eprint.iacr.org/2016/1123.pdf).
+///
+/// For every field operation, measures execution time on a fixed input class
+/// (all-zero first operand) and a random input class, interleaved at random,
+/// then runs Welch's t-test on the two distributions. |t| above the threshold
+/// means the timing depends on the input and the process exits non-zero.
+/// Positive controls are known to leak; if one of them passes, the
+/// measurement cannot see leaks at all, which is also a failure.
+///
+/// `cargo run --release --bin dudect -- [--measurements N] [--threshold T] [--target NAME]`
+use std::{hint::black_box, process::ExitCode, sync::OnceLock};
+
+use ark_std::rand::{self, Rng};
+use clap::Parser;
+use speedy_fields::{
+    barrett::Barrett,
+    ct::CtFr,
+    dispatch,
+    lazy::LazyFr,
+    prepared::PreparedMul,
+    stats::{welch_t, RunningStats},
+    CIOS,
+};
+
+/// Measurements above this percentile are dropped for the cropped test, which
+/// filters interrupts and other one-sided noise.
+const CROP_PERCENTILE: f64 = 0.90;
+
+/// Welch's t-test on fixed-vs-random timings of every field operation.
+#[derive(Debug, Parser)]
+struct Args {
+    /// Timed calls per target, split at random between the two classes.
+    #[arg(long, default_value_t = 1_000_000)]
+    measurements: usize,
+
+    /// |t| above which a target is reported as leaking; 10 is dudect's
+    /// "definitely not constant time" bound.
+    #[arg(long, default_value_t = 10.0)]
+    threshold: f64,
+
+    /// Run only the target with this name, e.g. `CtFr::mul`.
+    #[arg(long)]
+    target: Option<String>,
+}
+
+struct Target {
+    name: &'static str,
+    /// Whether the random class is drawn below r. Operations that are meant
+    /// to reduce arbitrary limbs get unreduced inputs instead.
+    reduced_inputs: bool,
+    /// A positive control: the target must be reported as leaking.
+    leaks: bool,
+    op: fn(&[u64; 4], &[u64; 4]) -> [u64; 4],
+}
+
+impl Target {
+    const fn new(name: &'static str, op: fn(&[u64; 4], &[u64; 4]) -> [u64; 4]) -> Self {
+        Self { name, reduced_inputs: true, leaks: false, op }
+    }
+}
+
+fn targets() -> Vec<Target> {
+    vec![
+        Target::new("CIOS::mul", |a, b| *fr(a).mul(&fr(b)).limbs()),
+        Target::new("CIOS::mul_edmsm", |a, b| *fr(a).mul_edmsm(&fr(b)).limbs()),
+        Target::new("CIOS::mul_sos", |a, b| *fr(a).mul_sos(&fr(b)).limbs()),
+        Target::new("CIOS::mul_fios", |a, b| *fr(a).mul_fios(&fr(b)).limbs()),
+        Target::new("CIOS::mul_cihs", |a, b| *fr(a).mul_cihs(&fr(b)).limbs()),
+        Target::new("CIOS::mul_karatsuba", |a, b| *fr(a).mul_karatsuba(&fr(b)).limbs()),
+        Target::new("CIOS::mul_u64", |a, b| *fr(a).mul_u64(b[0]).limbs()),
+        Target::new("CIOS::add", |a, b| *fr(a).add(&fr(b)).limbs()),
+        Target::new("CIOS::sub", |a, b| *fr(a).sub(&fr(b)).limbs()),
+        Target {
+            name: "CIOS::sub_mod_if_big",
+            reduced_inputs: false,
+            leaks: false,
+            op: |a, _| {
+                let mut t = *a;
+                CIOS::sub_mod_if_big(&mut t);
+                t
+            },
+        },
+        Target::new("dispatch::mul", |a, b| *dispatch::mul(&fr(a), &fr(b)).limbs()),
+        Target::new("Barrett::mul", |a, b| Barrett(*a).mul(&Barrett(*b)).0),
+        Target::new("LazyFr::mul", |a, b| *LazyFr::from_cios(&fr(a)).mul(&LazyFr::from_cios(&fr(b))).limbs()),
+        Target::new("PreparedMul::mul", |a, _| *prepared_constant().mul(&fr(a)).limbs()),
+        Target::new("CtFr::mul", |a, b| *CtFr::from_cios(&fr(a)).mul(&CtFr::from_cios(&fr(b))).limbs()),
+        Target::new("CtFr::add", |a, b| *CtFr::from_cios(&fr(a)).add(&CtFr::from_cios(&fr(b))).limbs()),
+        Target::new("CtFr::sub", |a, b| *CtFr::from_cios(&fr(a)).sub(&CtFr::from_cios(&fr(b))).limbs()),
+        Target::new("CtFr::invert", |a, _| *CtFr::from_cios(&fr(a)).invert().unwrap_or(CtFr::ZERO).limbs()),
+        // Returns early on zero, which is exactly the fixed class.
+        Target {
+            name: "CIOS::inverse",
+            reduced_inputs: true,
+            leaks: true,
+            op: |a, _| *fr(a).inverse().unwrap_or(CIOS::ZERO).limbs(),
+        },
+    ]
+}
+
+/// Inputs are drawn in range for each target, so skip the checks.
+#[inline(always)]
+fn fr(limbs: &[u64; 4]) -> CIOS {
+    CIOS::from_raw_unchecked(*limbs)
+}
+
+/// Precomputation is done once, only the multiplication itself is timed.
+fn prepared_constant() -> &'static PreparedMul {
+    static PREPARED: OnceLock<PreparedMul> = OnceLock::new();
+    PREPARED.get_or_init(|| PreparedMul::new(&CIOS::from_canonical([7, 0, 0, 0])))
+}
+
+struct Report {
+    fixed: RunningStats,
+    random: RunningStats,
+    t: f64,
+    t_cropped: f64,
+}
+
+impl Report {
+    fn max_t(&self) -> f64 {
+        self.t.abs().max(self.t_cropped.abs())
+    }
+}
+
+fn measure(target: &Target, measurements: usize) -> Report {
+    let mut rng = rand::thread_rng();
+    let classes: Vec<bool> = (0..measurements).map(|_| rng.gen()).collect();
+    let mut rand_limbs = |reduced: bool| {
+        if reduced {
+            *CIOS::rand().limbs()
+        } else {
+            [rng.gen(), rng.gen(), rng.gen(), rng.gen()]
+        }
+    };
+
+    let inputs: Vec<([u64; 4], [u64; 4])> = classes
+        .iter()
+        .map(|&random| {
+            let a = if random { rand_limbs(target.reduced_inputs) } else { [0; 4] };
+            (a, rand_limbs(true))
+        })
+        .collect();
+
+    let mut timings = Vec::with_capacity(measurements);
+    for (a, b) in inputs.iter() {
+        let start = timestamp();
+        black_box((target.op)(black_box(a), black_box(b)));
+        timings.push(timestamp().wrapping_sub(start));
+    }
+
+    let mut sorted = timings.clone();
+    sorted.sort_unstable();
+    let crop = sorted[((measurements - 1) as f64 * CROP_PERCENTILE) as usize];
+
+    let (mut fixed, mut random) = (RunningStats::new(), RunningStats::new());
+    let (mut fixed_cropped, mut random_cropped) = (RunningStats::new(), RunningStats::new());
+    for (&is_random, &timing) in classes.iter().zip(&timings) {
+        let (all, cropped) = if is_random { (&mut random, &mut random_cropped) } else { (&mut fixed, &mut fixed_cropped) };
+        all.push(timing as f64);
+        if timing <= crop {
+            cropped.push(timing as f64);
+        }
+    }
+
+    Report {
+        t: welch_t(&fixed, &random),
+        t_cropped: welch_t(&fixed_cropped, &random_cropped),
+        fixed,
+        random,
+    }
+}
+
+/// Serialised cycle counter on x86_64.
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn timestamp() -> u64 {
+    use std::arch::x86_64::{_mm_lfence, _rdtsc};
+    unsafe {
+        _mm_lfence();
+        let t = _rdtsc();
+        _mm_lfence();
+        t
+    }
+}
+
+/// Nanoseconds since the first call.
+#[cfg(not(target_arch = "x86_64"))]
+#[inline(always)]
+fn timestamp() -> u64 {
+    use std::time::Instant;
+    static START: OnceLock<Instant> = OnceLock::new();
+    START.get_or_init(Instant::now).elapsed().as_nanos() as u64
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    assert!(args.measurements >= 2, "--measurements must be at least 2");
+
+    let unit = if cfg!(target_arch = "x86_64") { "cycles" } else { "ns" };
+    println!("{:<22} {:>14} {:>14} {:>10} {:>10}  verdict", "target", format!("fixed {unit}"), format!("random {unit}"), "t", "t cropped");
+
+    let mut failures = Vec::new();
+    for target in targets().iter().filter(|t| args.target.as_deref().is_none_or(|name| t.name == name)) {
+        let report = measure(target, args.measurements);
+        let leaks = report.max_t() > args.threshold;
+        let verdict = match (leaks, target.leaks) {
+            (false, false) => "ok",
+            (true, false) => "LEAKS",
+            (true, true) => "leaks (control)",
+            (false, true) => "NOT DETECTED (control)",
+        };
+        println!(
+            "{:<22} {:>14.1} {:>14.1} {:>10.2} {:>10.2}  {}",
+            target.name,
+            report.fixed.mean(),
+            report.random.mean(),
+            report.t,
+            report.t_cropped,
+            verdict
+        );
+        if leaks != target.leaks {
+            failures.push(target.name);
+        }
+    }
+
+    if failures.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        eprintln!("verdict differs from the expected one at |t| > {}: {}", args.threshold, failures.join(", "));
+        ExitCode::FAILURE
+    }
+}
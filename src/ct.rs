@@ -0,0 +1,148 @@
+/// Constant-time Bn254 Fr in Montgomery form, for secret witness data.
+///
+/// `CIOS` branches on `bigint_greater_eq` (which returns early) and on the
+/// final conditional subtraction, so its timing depends on the operands.
+/// `CtFr` runs the same EdMSM loop but always computes both candidates of a
+/// conditional subtraction and picks one with a masked select, and exposes
+/// fallible operations through `subtle::CtOption`. `CIOS` stays the fast
+/// variable-time path.
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+use crate::{adc, edmsm_unreduced, field, sbb, CIOS, MODULUS, MODULUS_MINUS_TWO, R, R2};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CtFr([u64; 4]);
+
+impl CtFr {
+    pub const ZERO: Self = Self([0; 4]);
+    pub const ONE: Self = Self(R);
+
+    /// Wraps Montgomery-form limbs, which must be below r.
+    pub fn new(limbs: [u64; 4]) -> CtOption<Self> {
+        let (_, borrow) = sub_limbs(&limbs, &MODULUS);
+        CtOption::new(Self(limbs), Choice::from(borrow as u8))
+    }
+
+    /// Converts a canonical integer, which must be below r.
+    pub fn from_canonical(limbs: [u64; 4]) -> CtOption<Self> {
+        Self::new(limbs).map(|a| a.mul(&Self(R2)))
+    }
+
+    /// `a` must be reduced.
+    #[inline(always)]
+    pub fn from_cios(a: &CIOS) -> Self {
+        Self(a.0)
+    }
+
+    #[inline(always)]
+    pub fn to_cios(&self) -> CIOS {
+        CIOS(self.0)
+    }
+
+    #[inline(always)]
+    pub fn limbs(&self) -> &[u64; 4] {
+        &self.0
+    }
+
+    #[inline(always)]
+    pub fn mul(&self, rhs: &Self) -> Self {
+        Self(sub_modulus_if_ge(edmsm_unreduced(&self.0, &rhs.0)))
+    }
+
+    #[inline(always)]
+    pub fn square(&self) -> Self {
+        self.mul(self)
+    }
+
+    #[inline(always)]
+    pub fn add(&self, rhs: &Self) -> Self {
+        // r < 2^254, so the sum cannot carry out of the top limb
+        let (sum, _) = add_limbs(&self.0, &rhs.0);
+        Self(sub_modulus_if_ge(sum))
+    }
+
+    #[inline(always)]
+    pub fn sub(&self, rhs: &Self) -> Self {
+        let (diff, borrow) = sub_limbs(&self.0, &rhs.0);
+        let (wrapped, _) = add_limbs(&diff, &MODULUS);
+        Self(select_limbs(&diff, &wrapped, Choice::from(borrow as u8)))
+    }
+
+    #[inline(always)]
+    pub fn neg(&self) -> Self {
+        Self::ZERO.sub(self)
+    }
+
+    #[inline(always)]
+    pub fn double(&self) -> Self {
+        self.add(self)
+    }
+
+    pub fn is_zero(&self) -> Choice {
+        self.ct_eq(&Self::ZERO)
+    }
+
+    /// `self^(r - 2)`, which is `self^{-1}` for non-zero inputs. The exponent
+    /// is fixed, so the sequence of operations does not depend on `self`.
+    pub fn invert(&self) -> CtOption<Self> {
+        CtOption::new(field::pow(self, &MODULUS_MINUS_TWO), !self.is_zero())
+    }
+}
+
+impl ConstantTimeEq for CtFr {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl ConditionallySelectable for CtFr {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self(select_limbs(&a.0, &b.0, choice))
+    }
+}
+
+impl PartialEq for CtFr {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for CtFr {}
+
+/// `a - r` if `a >= r`, else `a`, without branching.
+#[inline(always)]
+fn sub_modulus_if_ge(a: [u64; 4]) -> [u64; 4] {
+    let (diff, borrow) = sub_limbs(&a, &MODULUS);
+    select_limbs(&diff, &a, Choice::from(borrow as u8))
+}
+
+/// Picks `b` when `choice` is set, else `a`.
+#[inline(always)]
+fn select_limbs(a: &[u64; 4], b: &[u64; 4], choice: Choice) -> [u64; 4] {
+    [
+        u64::conditional_select(&a[0], &b[0], choice),
+        u64::conditional_select(&a[1], &b[1], choice),
+        u64::conditional_select(&a[2], &b[2], choice),
+        u64::conditional_select(&a[3], &b[3], choice),
+    ]
+}
+
+#[inline(always)]
+fn add_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], u64) {
+    let mut t = *a;
+    let mut carry = 0;
+    for (limb, b) in t.iter_mut().zip(b) {
+        (*limb, carry) = adc(*limb, *b, carry);
+    }
+    (t, carry)
+}
+
+#[inline(always)]
+fn sub_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], u64) {
+    let mut t = *a;
+    let mut borrow = 0;
+    for (limb, b) in t.iter_mut().zip(b) {
+        borrow = sbb(limb, *b, borrow);
+    }
+    (t, borrow)
+}
@@ -0,0 +1,257 @@
+/// Runtime selection of the fastest multiplication kernel for the host CPU.
+/// The choice is made once, on first use, and cached as a plain function pointer
+/// so the hot path is a single indirect call.
+///
+/// There is no AVX-512 IFMA kernel: IFMA only pays off on eight independent
+/// products at once in 52-bit limbs, which the one-product signature of `mul`
+/// cannot express. `report::HostInfo` still records whether the CPU has it.
+use std::sync::OnceLock;
+
+use crate::CIOS;
+
+/// Environment variable used to force a particular kernel, e.g. for benchmarking
+/// `SPEEDY_FIELDS_KERNEL=cios cargo run --release`.
+pub const KERNEL_ENV_VAR: &str = "SPEEDY_FIELDS_KERNEL";
+
+type MulFn = fn(&CIOS, &CIOS) -> CIOS;
+
+static SELECTED: OnceLock<(Kernel, MulFn)> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kernel {
+    /// Portable CIOS, see `CIOS::mul`.
+    Cios,
+    /// Portable CIOS with the EdMSM carry optimization, see `CIOS::mul_edmsm`.
+    EdMsm,
+    /// Hand-written EdMSM CIOS in x86_64 assembly: `mulx` products with the
+    /// multiplication and reduction sums on two independent carry chains
+    /// (`adcx` on CF, `adox` on OF). Needs ADX and BMI2.
+    Adx,
+}
+
+impl Kernel {
+    pub const ALL: [Kernel; 3] = [Kernel::Cios, Kernel::EdMsm, Kernel::Adx];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Kernel::Cios => "cios",
+            Kernel::EdMsm => "edmsm",
+            Kernel::Adx => "adx",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|kernel| kernel.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Whether the host CPU can run this kernel.
+    pub fn is_supported(self) -> bool {
+        match self {
+            Kernel::Cios | Kernel::EdMsm => true,
+            Kernel::Adx => adx_supported(),
+        }
+    }
+
+    /// Best kernel the host CPU supports, ignoring `KERNEL_ENV_VAR`.
+    pub fn detect() -> Self {
+        if Kernel::Adx.is_supported() {
+            Kernel::Adx
+        } else {
+            Kernel::EdMsm
+        }
+    }
+
+    /// Multiplies with this kernel, bypassing the selection. Same input
+    /// range as `CIOS::mul_edmsm`.
+    ///
+    /// Panics if the host CPU cannot run the kernel.
+    pub fn mul(self, a: &CIOS, b: &CIOS) -> CIOS {
+        assert!(self.is_supported(), "the {} kernel is not supported on this CPU", self.name());
+        (self.mul_fn())(a, b)
+    }
+
+    fn mul_fn(self) -> MulFn {
+        match self {
+            Kernel::Cios => |a, b| a.mul(b),
+            Kernel::EdMsm => |a, b| a.mul_edmsm(b),
+            Kernel::Adx => mul_adx,
+        }
+    }
+}
+
+/// Kernel used by `mul`, i.e. `resolve` of `KERNEL_ENV_VAR` as it was on
+/// first use.
+pub fn selected() -> Kernel {
+    selected_entry().0
+}
+
+/// The kernel `requested` names, or `Kernel::detect` for `None`.
+///
+/// Panics if it names an unknown kernel or one the CPU cannot run, since
+/// silently falling back would make benchmark numbers lie.
+pub fn resolve(requested: Option<&str>) -> Kernel {
+    let Some(name) = requested else {
+        return Kernel::detect();
+    };
+    let kernel = Kernel::from_name(name).unwrap_or_else(|| {
+        panic!("{KERNEL_ENV_VAR}={name:?} is not one of {:?}", Kernel::ALL.map(Kernel::name))
+    });
+    assert!(kernel.is_supported(), "{KERNEL_ENV_VAR}={name:?} is not supported on this CPU");
+    kernel
+}
+
+/// Montgomery multiplication using the best available kernel.
+#[inline(always)]
+pub fn mul(a: &CIOS, b: &CIOS) -> CIOS {
+    (selected_entry().1)(a, b)
+}
+
+#[inline(always)]
+fn selected_entry() -> &'static (Kernel, MulFn) {
+    SELECTED.get_or_init(|| {
+        let kernel = resolve(std::env::var(KERNEL_ENV_VAR).ok().as_deref());
+        (kernel, kernel.mul_fn())
+    })
+}
+
+#[cfg(target_arch = "x86_64")]
+fn adx_supported() -> bool {
+    is_x86_feature_detected!("adx") && is_x86_feature_detected!("bmi2")
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn adx_supported() -> bool {
+    false
+}
+
+/// `r` followed by `-r^{-1} mod 2^64`, addressed as `[m]` .. `[m + 32]`.
+#[cfg(target_arch = "x86_64")]
+static MODULUS_AND_INV: [u64; 5] = [crate::MODULUS[0], crate::MODULUS[1], crate::MODULUS[2], crate::MODULUS[3], crate::INV];
+
+/// One reduction step: `m = t0 * inv`, then `(t + m * r) / 2^64` with the
+/// high and low halves of `m * r` on the CF and OF chains. Leaves the new
+/// accumulator in `t0..t3`.
+#[cfg(target_arch = "x86_64")]
+macro_rules! adx_reduce {
+    () => {
+        concat!(
+            "mov rdx, qword ptr [{m} + 32]\n",
+            "imul rdx, {t0}\n",
+            "xor {lo:e}, {lo:e}\n",
+            "mulx {hi}, {lo}, qword ptr [{m}]\n",
+            "adcx {lo}, {t0}\n",
+            "mov {t0}, {hi}\n",
+            "adcx {t0}, {t1}\n",
+            "mulx {t1}, {lo}, qword ptr [{m} + 8]\n",
+            "adox {t0}, {lo}\n",
+            "adcx {t1}, {t2}\n",
+            "mulx {t2}, {lo}, qword ptr [{m} + 16]\n",
+            "adox {t1}, {lo}\n",
+            "adcx {t2}, {t3}\n",
+            "mulx {t3}, {lo}, qword ptr [{m} + 24]\n",
+            "adox {t2}, {lo}\n",
+            "mov {lo}, 0\n",
+            "adcx {t3}, {lo}\n",
+            "adox {t3}, {acc}\n",
+        )
+    };
+}
+
+/// `t += a * b[i]` for i > 0, low halves on OF and high halves on CF, with
+/// the word above `t3` in `acc`.
+#[cfg(target_arch = "x86_64")]
+macro_rules! adx_mul_add {
+    ($offset:literal) => {
+        concat!(
+            "xor {lo:e}, {lo:e}\n",
+            "mov rdx, qword ptr [{b} + ", $offset, "]\n",
+            "mulx {hi}, {lo}, qword ptr [{a}]\n",
+            "adox {t0}, {lo}\n",
+            "adcx {t1}, {hi}\n",
+            "mulx {hi}, {lo}, qword ptr [{a} + 8]\n",
+            "adox {t1}, {lo}\n",
+            "adcx {t2}, {hi}\n",
+            "mulx {hi}, {lo}, qword ptr [{a} + 16]\n",
+            "adox {t2}, {lo}\n",
+            "adcx {t3}, {hi}\n",
+            "mulx {acc}, {lo}, qword ptr [{a} + 24]\n",
+            "adox {t3}, {lo}\n",
+            "mov {lo}, 0\n",
+            "adcx {acc}, {lo}\n",
+            "adox {acc}, {lo}\n",
+        )
+    };
+}
+
+/// EdMSM CIOS (no carry word beyond `acc`, sound because r's top limb is
+/// below 2^62) for operands below 2r; the result is below 2r.
+///
+/// Needs ADX and BMI2.
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+unsafe fn edmsm_unreduced_adx(a: &[u64; 4], b: &[u64; 4]) -> [u64; 4] {
+    let (t0, t1, t2, t3): (u64, u64, u64, u64);
+    std::arch::asm!(
+        // t = a * b[0]
+        "xor {lo:e}, {lo:e}",
+        "mov rdx, qword ptr [{b}]",
+        "mulx {t1}, {t0}, qword ptr [{a}]",
+        "mulx {t2}, {lo}, qword ptr [{a} + 8]",
+        "adox {t1}, {lo}",
+        "mulx {t3}, {lo}, qword ptr [{a} + 16]",
+        "adox {t2}, {lo}",
+        "mulx {acc}, {lo}, qword ptr [{a} + 24]",
+        "adox {t3}, {lo}",
+        "mov {lo}, 0",
+        "adox {acc}, {lo}",
+        adx_reduce!(),
+        adx_mul_add!("8"),
+        adx_reduce!(),
+        adx_mul_add!("16"),
+        adx_reduce!(),
+        adx_mul_add!("24"),
+        adx_reduce!(),
+        a = in(reg) a.as_ptr(),
+        b = in(reg) b.as_ptr(),
+        m = in(reg) MODULUS_AND_INV.as_ptr(),
+        t0 = out(reg) t0,
+        t1 = out(reg) t1,
+        t2 = out(reg) t2,
+        t3 = out(reg) t3,
+        acc = out(reg) _,
+        lo = out(reg) _,
+        hi = out(reg) _,
+        out("rdx") _,
+        options(pure, readonly, nostack),
+    );
+    [t0, t1, t2, t3]
+}
+
+#[cfg(target_arch = "x86_64")]
+fn mul_adx(a: &CIOS, b: &CIOS) -> CIOS {
+    // Only reachable once `adx_supported` has returned true.
+    unsafe { mul_adx_unchecked(a, b) }
+}
+
+/// The ADX kernel without the CPU check, exposed so the `asm` binary can
+/// disassemble its body; everything else goes through `Kernel::Adx`.
+///
+/// # Safety
+/// The CPU must support ADX and BMI2, i.e. `Kernel::Adx.is_supported()`.
+#[cfg(target_arch = "x86_64")]
+#[doc(hidden)]
+#[inline(always)]
+pub unsafe fn mul_adx_unchecked(a: &CIOS, b: &CIOS) -> CIOS {
+    debug_assert!(
+        !crate::bigint_greater_eq(a.limbs(), &crate::TWO_MODULUS) && !crate::bigint_greater_eq(b.limbs(), &crate::TWO_MODULUS),
+        "EdMSM operands must be below 2r"
+    );
+    CIOS::from_raw_unchecked(crate::reduce_once(edmsm_unreduced_adx(a.limbs(), b.limbs())))
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn mul_adx(_: &CIOS, _: &CIOS) -> CIOS {
+    unreachable!("ADX kernel selected on a non-x86_64 target")
+}
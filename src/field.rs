@@ -0,0 +1,209 @@
+/// Arithmetic shared by every Bn254 Fr representation in this crate, so that
+/// tests and tools can be written once and run against each of them.
+///
+/// Elements enter and leave through canonical integers below r; what each
+/// type stores internally (Montgomery form, canonical form, redundant
+/// [0, 2r) limbs) is its own business.
+use std::fmt::Debug;
+
+use crate::{barrett::Barrett, ct::CtFr, lazy::LazyFr, CIOS, MODULUS_MINUS_TWO};
+
+pub trait FieldOps: Copy + Debug + PartialEq {
+    const NAME: &'static str;
+
+    fn zero() -> Self;
+    fn one() -> Self;
+
+    /// `limbs` must be below r.
+    fn from_canonical(limbs: [u64; 4]) -> Self;
+    fn to_canonical(&self) -> [u64; 4];
+
+    fn add(&self, rhs: &Self) -> Self;
+    fn sub(&self, rhs: &Self) -> Self;
+    fn mul(&self, rhs: &Self) -> Self;
+
+    fn neg(&self) -> Self {
+        Self::zero().sub(self)
+    }
+
+    fn square(&self) -> Self {
+        self.mul(self)
+    }
+
+    /// `None` for zero. The default raises to `r - 2` with this type's own
+    /// `mul`, so implementations are compared like for like.
+    fn inverse(&self) -> Option<Self> {
+        if *self == Self::zero() {
+            return None;
+        }
+        Some(pow(self, &MODULUS_MINUS_TWO))
+    }
+}
+
+/// `x^exp` for a little-endian 256-bit exponent, by left-to-right
+/// square-and-multiply. Branches on the bits of `exp`, which must be public;
+/// the sequence of operations does not depend on `x`.
+pub fn pow<F: FieldOps>(x: &F, exp: &[u64; 4]) -> F {
+    let mut acc = F::one();
+    for limb in exp.iter().rev() {
+        for bit in (0..64).rev() {
+            acc = acc.square();
+            if (limb >> bit) & 1 == 1 {
+                acc = acc.mul(x);
+            }
+        }
+    }
+    acc
+}
+
+impl FieldOps for CIOS {
+    const NAME: &'static str = "CIOS";
+
+    fn zero() -> Self {
+        Self::ZERO
+    }
+
+    fn one() -> Self {
+        Self::ONE
+    }
+
+    fn from_canonical(limbs: [u64; 4]) -> Self {
+        CIOS::from_canonical(limbs)
+    }
+
+    fn to_canonical(&self) -> [u64; 4] {
+        CIOS::to_canonical(self)
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        CIOS::add(self, rhs)
+    }
+
+    fn sub(&self, rhs: &Self) -> Self {
+        CIOS::sub(self, rhs)
+    }
+
+    fn mul(&self, rhs: &Self) -> Self {
+        CIOS::mul(self, rhs)
+    }
+
+    fn neg(&self) -> Self {
+        CIOS::neg(self)
+    }
+
+    fn square(&self) -> Self {
+        CIOS::square(self)
+    }
+}
+
+impl FieldOps for Barrett {
+    const NAME: &'static str = "Barrett";
+
+    fn zero() -> Self {
+        Self([0; 4])
+    }
+
+    fn one() -> Self {
+        Self([1, 0, 0, 0])
+    }
+
+    fn from_canonical(limbs: [u64; 4]) -> Self {
+        Self(limbs)
+    }
+
+    fn to_canonical(&self) -> [u64; 4] {
+        self.0
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        Barrett::add(self, rhs)
+    }
+
+    fn sub(&self, rhs: &Self) -> Self {
+        Barrett::sub(self, rhs)
+    }
+
+    fn mul(&self, rhs: &Self) -> Self {
+        Barrett::mul(self, rhs)
+    }
+}
+
+impl FieldOps for LazyFr {
+    const NAME: &'static str = "LazyFr";
+
+    fn zero() -> Self {
+        Self::from_cios(&CIOS::ZERO)
+    }
+
+    fn one() -> Self {
+        Self::from_cios(&CIOS::ONE)
+    }
+
+    fn from_canonical(limbs: [u64; 4]) -> Self {
+        Self::from_cios(&CIOS::from_canonical(limbs))
+    }
+
+    fn to_canonical(&self) -> [u64; 4] {
+        self.normalize().to_canonical()
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        LazyFr::add(self, rhs)
+    }
+
+    fn sub(&self, rhs: &Self) -> Self {
+        LazyFr::sub(self, rhs)
+    }
+
+    fn mul(&self, rhs: &Self) -> Self {
+        LazyFr::mul(self, rhs)
+    }
+
+    fn square(&self) -> Self {
+        LazyFr::square(self)
+    }
+}
+
+impl FieldOps for CtFr {
+    const NAME: &'static str = "CtFr";
+
+    fn zero() -> Self {
+        Self::ZERO
+    }
+
+    fn one() -> Self {
+        Self::ONE
+    }
+
+    fn from_canonical(limbs: [u64; 4]) -> Self {
+        CtFr::from_canonical(limbs).expect("canonical limbs must be below r")
+    }
+
+    fn to_canonical(&self) -> [u64; 4] {
+        self.to_cios().to_canonical()
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        CtFr::add(self, rhs)
+    }
+
+    fn sub(&self, rhs: &Self) -> Self {
+        CtFr::sub(self, rhs)
+    }
+
+    fn mul(&self, rhs: &Self) -> Self {
+        CtFr::mul(self, rhs)
+    }
+
+    fn neg(&self) -> Self {
+        CtFr::neg(self)
+    }
+
+    fn square(&self) -> Self {
+        CtFr::square(self)
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        CtFr::invert(self).into()
+    }
+}
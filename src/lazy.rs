@@ -0,0 +1,108 @@
+/// Redundant representation of Bn254 Fr in Montgomery form, kept in [0, 2r)
+/// instead of [0, r) so multiplication can skip the final conditional
+/// subtraction. Values are only normalised when compared or serialised.
+///
+/// Bounds, with R = 2^256 and 4r < R (r < 2^254):
+/// - `mul`: for a, b < 2r the EdMSM loop returns (ab + mr) / R with m < R,
+///   so the result is below 4r^2/R + r < 2r.
+/// - `add`: a + b < 4r < R fits in four limbs; one conditional subtraction
+///   of 2r brings it back below 2r. Letting sums drift up to 4r is not
+///   possible since (4r)^2/R + r > 4r for Bn254.
+/// - `sub`: a - b > -2r, so one conditional addition of 2r lands in [0, 2r).
+use crate::{add_with_carry, bigint_greater_eq, edmsm_unreduced, sub_with_borrow, CIOS, MODULUS, TWO_MODULUS};
+
+#[derive(Debug, Clone, Copy)]
+pub struct LazyFr([u64; 4]);
+
+impl LazyFr {
+    /// Wraps redundant limbs, returning `None` unless they are below 2r.
+    pub fn new(limbs: [u64; 4]) -> Option<Self> {
+        (!bigint_greater_eq(&limbs, &TWO_MODULUS)).then_some(Self(limbs))
+    }
+
+    #[inline(always)]
+    pub fn from_cios(a: &CIOS) -> Self {
+        debug_assert!(!bigint_greater_eq(&a.0, &MODULUS), "CIOS input must be reduced");
+        Self(a.0)
+    }
+
+    /// The redundant limbs, anywhere in [0, 2r).
+    #[inline(always)]
+    pub fn limbs(&self) -> &[u64; 4] {
+        &self.0
+    }
+
+    /// Reduces into [0, r).
+    #[inline(always)]
+    pub fn normalize(&self) -> CIOS {
+        let mut t = self.0;
+        CIOS::sub_mod_if_big(&mut t);
+        CIOS(t)
+    }
+
+    #[inline(always)]
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.normalize().to_bytes()
+    }
+
+    /// Montgomery product with no final subtraction.
+    #[inline(always)]
+    pub fn mul(&self, rhs: &Self) -> Self {
+        let t = edmsm_unreduced(&self.0, &rhs.0);
+        debug_assert!(!bigint_greater_eq(&t, &TWO_MODULUS), "mul left [0, 2r)");
+        Self(t)
+    }
+
+    #[inline(always)]
+    pub fn square(&self) -> Self {
+        self.mul(self)
+    }
+
+    #[inline(always)]
+    pub fn add(&self, rhs: &Self) -> Self {
+        let mut t = self.0;
+        let carry = add_with_carry(&mut t, &rhs.0);
+        debug_assert!(!carry, "sum of two values below 2r cannot exceed 2^256");
+        if bigint_greater_eq(&t, &TWO_MODULUS) {
+            sub_with_borrow(&mut t, &TWO_MODULUS);
+        }
+        debug_assert!(!bigint_greater_eq(&t, &TWO_MODULUS), "add left [0, 2r)");
+        Self(t)
+    }
+
+    #[inline(always)]
+    pub fn sub(&self, rhs: &Self) -> Self {
+        let mut t = self.0;
+        if sub_with_borrow(&mut t, &rhs.0) {
+            add_with_carry(&mut t, &TWO_MODULUS);
+        }
+        debug_assert!(!bigint_greater_eq(&t, &TWO_MODULUS), "sub left [0, 2r)");
+        Self(t)
+    }
+
+    #[inline(always)]
+    pub fn double(&self) -> Self {
+        self.add(self)
+    }
+}
+
+impl From<CIOS> for LazyFr {
+    fn from(a: CIOS) -> Self {
+        Self::from_cios(&a)
+    }
+}
+
+impl From<LazyFr> for CIOS {
+    fn from(a: LazyFr) -> Self {
+        a.normalize()
+    }
+}
+
+/// Equality of the represented field elements, not of the redundant limbs.
+impl PartialEq for LazyFr {
+    fn eq(&self, other: &Self) -> bool {
+        self.normalize() == other.normalize()
+    }
+}
+
+impl Eq for LazyFr {}
@@ -4,6 +4,20 @@
 use ark_std::rand;
 use unroll::unroll_for_loops;
 
+pub mod backend;
+pub mod barrett;
+pub mod ct;
+pub mod dispatch;
+pub mod field;
+pub mod lazy;
+#[cfg(all(feature = "perf", target_os = "linux"))]
+pub mod perf;
+pub mod prepared;
+pub mod report;
+pub mod soa;
+pub mod stats;
+pub mod wide;
+
 /// INV = -(r^{-1} mod 2^64) mod 2^64
 const INV: u64 = 0xc2e1f593efffffff;
 
@@ -34,23 +48,98 @@ const R3: [u64; 4] = [
     0x0cf8594b7fcc657c,
 ];
 
-const MODULUS: [u64; 4] = [
+/// `floor(2^317 / r)`, used to estimate quotients in `mul_u64`.
+const SMALL_MUL_MU: u64 = 0xa948e8c4c474094f;
+
+/// The Bn254 scalar field modulus r, little-endian limbs.
+/// `0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001`
+pub const MODULUS: [u64; 4] = [
     4891460686036598785u64,
     2896914383306846353u64,
     13281191951274694749u64,
     3486998266802970665u64,
 ];
 
+/// `r - 2`, the Fermat inversion exponent. Public, so square-and-multiply
+/// may branch on its bits.
+pub(crate) const MODULUS_MINUS_TWO: [u64; 4] = [MODULUS[0] - 2, MODULUS[1], MODULUS[2], MODULUS[3]];
+
+/// `2r`
+/// `0x60c89ce5c263405370a08b6d0302b0ba5067d090f372e12287c3eb27e0000002`
+const TWO_MODULUS: [u64; 4] = [
+    0x87c3eb27e0000002,
+    0x5067d090f372e122,
+    0x70a08b6d0302b0ba,
+    0x60c89ce5c2634053,
+];
+
 const N: usize = 4;
 
-#[derive(Debug, Clone)]
-pub struct CIOS(pub [u64; 4]);
+/// Bn254 Fr in Montgomery form. `new` only accepts reduced limbs; limbs
+/// from `from_raw_unchecked` are not checked, so each operation documents
+/// the inputs it is correct for (enforced by `debug_assert!`s on the
+/// multipliers and exercised by the targets under `fuzz/`):
+/// - `mul`, `mul_sos`, `mul_fios`, `mul_cihs`, `mul_karatsuba`: the integer
+///   product of the limbs must be below `r * 2^256`, e.g. one operand
+///   reduced and the other any 256-bit value.
+/// - `mul_edmsm`: both operands below 2r.
+/// - `redc`: below `r * 2^256`.
+/// - `from_canonical`, `to_canonical`, `to_bytes`, `sub_mod_if_big`: any
+///   256-bit value.
+/// - everything else: reduced operands.
+///
+/// Inside those ranges results are reduced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CIOS([u64; 4]);
+
+/// Why a limb array was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldError {
+    /// The limbs encode a value greater than or equal to r.
+    NotReduced([u64; 4]),
+}
+
+impl std::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotReduced(limbs) => write!(f, "limbs {limbs:x?} are not below the Bn254 Fr modulus"),
+        }
+    }
+}
+
+impl std::error::Error for FieldError {}
 
 impl CIOS {
+    pub const ZERO: Self = Self([0; 4]);
+    /// `1` in Montgomery form, i.e. `R mod r`.
+    pub const ONE: Self = Self(R);
+
+    /// Wraps Montgomery-form limbs, which must be below r.
+    pub fn new(limbs: [u64; 4]) -> Result<Self, FieldError> {
+        if bigint_greater_eq(&limbs, &MODULUS) {
+            return Err(FieldError::NotReduced(limbs));
+        }
+        Ok(Self(limbs))
+    }
+
+    /// Wraps limbs without checking them, for hot paths and for callers that
+    /// deliberately stay inside the wider ranges listed above.
+    #[inline(always)]
+    pub const fn from_raw_unchecked(limbs: [u64; 4]) -> Self {
+        Self(limbs)
+    }
+
+    /// The Montgomery-form limbs.
+    #[inline(always)]
+    pub fn limbs(&self) -> &[u64; 4] {
+        &self.0
+    }
+
     /// Implements normal CIOS
     #[unroll_for_loops]
     #[inline(always)]
     pub fn mul(&self, rhs: &Self) -> Self {
+        debug_assert_product_in_range(self, rhs);
         let mut t: [u64; N] = [0u64; N];
         let mut c1 = 0u64;
         let mut c2;
@@ -87,51 +176,388 @@ impl CIOS {
     /// where D is word size. Fine for Bn254, problem for Secp256k1.
     /// Experimentally on ARM it's only marginally faster. Likely 1-4
     /// instructions.
-    #[unroll_for_loops]
+    /// Skipping the carry word is only sound for operands below 2r; outside
+    /// that range results can be wrong even modulo r.
     #[inline(always)]
     pub fn mul_edmsm(self, rhs: &Self) -> Self {
-        let mut t: [u64; N+1] = [0u64; N+1];
+        Self(reduce_once(edmsm_unreduced(&self.0, &rhs.0)))
+    }
+
+    /// Separated Operand Scanning: the full 512-bit product first, then a
+    /// separate word-by-word Montgomery reduction.
+    #[inline(always)]
+    pub fn mul_sos(&self, rhs: &Self) -> Self {
+        Self::redc(self.mul_wide(rhs))
+    }
+
+    /// Finely Integrated Operand Scanning: multiplication and reduction share
+    /// a single inner loop. The multiplication carry has to be rippled into
+    /// the upper words immediately, which is what usually makes FIOS lose
+    /// to CIOS in software.
+    #[unroll_for_loops]
+    #[inline(always)]
+    pub fn mul_fios(&self, rhs: &Self) -> Self {
+        debug_assert_product_in_range(self, rhs);
+        let mut t: [u64; N+2] = [0u64; N+2];
         for i in 0..4 {
-            let mut c: u64 = 0u64;
-            for j in 0..4 {
-                (t[j], c) = mac(t[j], self.0[j], rhs.0[i], c);
+            let (s, c) = mac(t[0], self.0[0], rhs.0[i], 0);
+            add_carry_at(&mut t, 1, c);
+            let m = s.wrapping_mul(INV);
+            let (_, mut c) = macx(s, m, MODULUS[0]);
+
+            for j in 1..4 {
+                let (s, c_mul) = mac(t[j], self.0[j], rhs.0[i], c);
+                add_carry_at(&mut t, j+1, c_mul);
+                (t[j-1], c) = macx(s, m, MODULUS[j]);
             }
-            t[N] = c;
+            (t[N-1], c) = adc(t[N], c, 0);
+            t[N] = t[N+1] + c;
+            t[N+1] = 0;
+        }
 
-            let m = t[0].wrapping_mul(INV); 
-            (_, c) = macx(t[0], m, MODULUS[0]);
+        let mut trunc_t: [u64; N] = t[0..N].try_into().unwrap();
+        Self::sub_mod_if_big(&mut trunc_t);
+        Self(trunc_t)
+    }
+
+    /// Coarsely Integrated Hybrid Scanning: the lower half of the product
+    /// (columns below N) is computed up front, and each upper column is
+    /// folded in right after the reduction step that shifts it into the top
+    /// word.
+    #[unroll_for_loops]
+    #[inline(always)]
+    pub fn mul_cihs(&self, rhs: &Self) -> Self {
+        debug_assert_product_in_range(self, rhs);
+        let mut t: [u64; N+2] = [0u64; N+2];
+        for i in 0..4 {
+            let mut c = 0u64;
+            for j in 0..N-i {
+                (t[i+j], c) = mac(t[i+j], self.0[j], rhs.0[i], c);
+            }
+            add_carry_at(&mut t, N, c);
+        }
 
+        for i in 0..4 {
+            let m = t[0].wrapping_mul(INV);
+            let (_, mut c) = macx(t[0], m, MODULUS[0]);
             for j in 1..4 {
                 (t[j-1], c) = mac(t[j], m, MODULUS[j], c);
             }
-            t[N-1] = t[N] + c;
+            (t[N-1], c) = adc(t[N], c, 0);
+            t[N] = t[N+1] + c;
+            t[N+1] = 0;
+
+            // Column N + i now sits at word N - 1.
+            for (j, b) in rhs.0.iter().enumerate().skip(i+1) {
+                let (s, c) = mac(t[N-1], self.0[N+i-j], *b, 0);
+                t[N-1] = s;
+                add_carry_at(&mut t, N, c);
+            }
         }
-        t[N] = 0;
 
         let mut trunc_t: [u64; N] = t[0..N].try_into().unwrap();
-        if bigint_greater_eq(&trunc_t, &MODULUS) {
-            let mut borrow = 0;
-            borrow = sbb(&mut trunc_t[0], MODULUS[0], borrow);
-            borrow = sbb(&mut trunc_t[1], MODULUS[1], borrow);
-            borrow = sbb(&mut trunc_t[2], MODULUS[2], borrow);
-            sbb(&mut trunc_t[3], MODULUS[3], borrow);
+        Self::sub_mod_if_big(&mut trunc_t);
+        Self(trunc_t)
+    }
+
+    /// Separated-operand Montgomery product where the 512-bit product comes
+    /// from one level of Karatsuba on 128-bit halves: 12 word multiplications
+    /// instead of 16, paid for with extra additions and carry handling.
+    #[inline(always)]
+    pub fn mul_karatsuba(&self, rhs: &Self) -> Self {
+        Self::redc(self.mul_wide_karatsuba(rhs))
+    }
+
+    /// Same result as `mul_wide`, computed as
+    /// `z0 + ((a0 + a1)(b0 + b1) - z0 - z2) * 2^128 + z2 * 2^256`.
+    #[inline(always)]
+    pub fn mul_wide_karatsuba(&self, rhs: &Self) -> [u64; 2*N] {
+        let (a0, a1) = ([self.0[0], self.0[1]], [self.0[2], self.0[3]]);
+        let (b0, b1) = ([rhs.0[0], rhs.0[1]], [rhs.0[2], rhs.0[3]]);
+        let z0 = mul_2x2(&a0, &b0);
+        let z2 = mul_2x2(&a1, &b1);
+
+        // 129-bit half sums, the top bit kept aside as a carry
+        let (sa, ca) = add_2x2(&a0, &a1);
+        let (sb, cb) = add_2x2(&b0, &b1);
+
+        // (sa + ca 2^128)(sb + cb 2^128) without multiplying by the carries
+        let p = mul_2x2(&sa, &sb);
+        let mut mid = [p[0], p[1], p[2], p[3], ca & cb];
+        let (sa_masked, sb_masked) = (sa.map(|x| x & 0u64.wrapping_sub(cb)), sb.map(|x| x & 0u64.wrapping_sub(ca)));
+        let mut carry;
+        (mid[2], carry) = adc(mid[2], sa_masked[0], 0);
+        (mid[3], carry) = adc(mid[3], sa_masked[1], carry);
+        mid[4] += carry;
+        (mid[2], carry) = adc(mid[2], sb_masked[0], 0);
+        (mid[3], carry) = adc(mid[3], sb_masked[1], carry);
+        mid[4] += carry;
+
+        let mut borrow = 0;
+        for (i, limb) in mid.iter_mut().enumerate() {
+            let z = if i < N { z0[i] } else { 0 };
+            borrow = sbb(limb, z, borrow);
+        }
+        let mut borrow = 0;
+        for (i, limb) in mid.iter_mut().enumerate() {
+            let z = if i < N { z2[i] } else { 0 };
+            borrow = sbb(limb, z, borrow);
         }
 
-        Self(trunc_t)
+        let mut t = [z0[0], z0[1], z0[2], z0[3], z2[0], z2[1], z2[2], z2[3]];
+        let mut carry = 0;
+        for (i, limb) in mid.iter().enumerate() {
+            (t[i+2], carry) = adc(t[i+2], *limb, carry);
+        }
+        t[7] += carry;
+        t
     }
 
+    /// Full 512-bit schoolbook product with no reduction, so several
+    /// products can be summed before paying for a single `redc`.
+    #[unroll_for_loops]
+    #[inline(always)]
+    pub fn mul_wide(&self, rhs: &Self) -> [u64; 2*N] {
+        let mut t = [0u64; 2*N];
+        for i in 0..4 {
+            let mut c = 0u64;
+            for j in 0..4 {
+                (t[i+j], c) = mac(t[i+j], self.0[i], rhs.0[j], c);
+            }
+            t[i+N] = c;
+        }
+        t
+    }
+
+    /// Montgomery reduction, returns `t * R^{-1} mod r` fully reduced.
+    /// Requires `t < r * 2^256`, which holds for any `mul_wide` of two reduced
+    /// elements. Larger sums should go through `wide::WideAccumulator`.
+    #[unroll_for_loops]
+    #[inline(always)]
+    pub fn redc(mut t: [u64; 2*N]) -> Self {
+        debug_assert!(!bigint_greater_eq(&[t[4], t[5], t[6], t[7]], &MODULUS), "redc input must be below r * 2^256");
+        let mut carry = 0u64;
+        for i in 0..4 {
+            let m = t[i].wrapping_mul(INV);
+            let (_, mut c) = macx(t[i], m, MODULUS[0]);
+            for j in 1..4 {
+                (t[i+j], c) = mac(t[i+j], m, MODULUS[j], c);
+            }
+            (t[i+N], carry) = adc(t[i+N], c, carry);
+        }
+        debug_assert_eq!(carry, 0, "redc input must be below r * 2^256");
+
+        let mut res: [u64; N] = t[N..].try_into().unwrap();
+        Self::sub_mod_if_big(&mut res);
+        Self(res)
+    }
+
+    /// Random reduced element: uniform 256-bit limbs brought below r.
     pub fn rand() -> Self {
         use rand::Rng;
         let mut rng = rand::thread_rng();
-        Self([rng.gen(), rng.gen(), rng.gen(), rng.gen()])
+        let mut t = [rng.gen(), rng.gen(), rng.gen(), rng.gen()];
+        while bigint_greater_eq(&t, &MODULUS) {
+            sub_with_borrow(&mut t, &MODULUS);
+        }
+        Self(t)
     }
 
+    /// Subtracts r once if `a >= r`. The result is only reduced for `a < 2r`.
     #[inline(always)]
     pub fn sub_mod_if_big(a: &mut [u64; 4]) {
         if bigint_greater_eq(a, &MODULUS) {
             sub_with_borrow(a, &MODULUS);
         }
     }
+
+    /// Converts a canonical integer into Montgomery form. Any 256-bit value is
+    /// accepted and reduced mod r.
+    #[inline(always)]
+    pub fn from_canonical(limbs: [u64; 4]) -> Self {
+        Self(limbs).mul(&Self(R2))
+    }
+
+    /// Leaves Montgomery form, returning the canonical integer below r.
+    #[inline(always)]
+    pub fn to_canonical(&self) -> [u64; 4] {
+        Self::redc([self.0[0], self.0[1], self.0[2], self.0[3], 0, 0, 0, 0]).0
+    }
+
+    /// Little-endian encoding of the canonical value, i.e. what gets hashed
+    /// or absorbed into a transcript.
+    #[inline(always)]
+    pub fn to_bytes(&self) -> [u8; 32] {
+        limbs_to_bytes(&self.to_canonical())
+    }
+
+    /// Multiplies by an integer without a Montgomery product: one row of
+    /// word products gives a 320-bit `t < 2^64 * r`, then
+    /// `q = ((t >> 254) * floor(2^317 / r)) >> 63` underestimates `t / r` by
+    /// at most 2, so two conditional subtractions finish the reduction.
+    /// `self` must be reduced.
+    ///
+    /// With `h = t >> 254` and `mu = 2^317 / r - eps`, the estimate misses
+    /// `t / r` by `((t / 2^254 - h) * mu + (t / 2^254) * eps) / 2^63 + 1`,
+    /// below `1.33 + 1.52 * 0.34 + 1 < 3` since `eps ~ 0.34`, so
+    /// `t - q * r < 3r`.
+    #[unroll_for_loops]
+    #[inline(always)]
+    pub fn mul_u64(&self, k: u64) -> Self {
+        debug_assert!(!bigint_greater_eq(&self.0, &MODULUS), "mul_u64 operand must be reduced");
+        let mut t = [0u64; N+1];
+        let mut c = 0u64;
+        for i in 0..4 {
+            (t[i], c) = mac(0, self.0[i], k, c);
+        }
+        t[N] = c;
+
+        let t_hi = (t[N] << 2) | (t[N-1] >> 62);
+        let q = (((t_hi as u128) * (SMALL_MUL_MU as u128)) >> 63) as u64;
+
+        let mut qr = [0u64; N+1];
+        let mut c = 0u64;
+        for i in 0..4 {
+            (qr[i], c) = mac(0, q, MODULUS[i], c);
+        }
+        qr[N] = c;
+        let mut borrow = 0u64;
+        for i in 0..5 {
+            borrow = sbb(&mut t[i], qr[i], borrow);
+        }
+        debug_assert!(borrow == 0 && t[N] == 0, "t - q * r must lie in [0, 3r)");
+
+        let res = reduce_once(reduce_once([t[0], t[1], t[2], t[3]]));
+        debug_assert!(!bigint_greater_eq(&res, &MODULUS), "t - q * r must lie in [0, 3r)");
+        Self(res)
+    }
+
+    #[inline(always)]
+    pub fn mul_u32(&self, k: u32) -> Self {
+        self.mul_u64(k as u64)
+    }
+
+    #[inline(always)]
+    pub fn double(&self) -> Self {
+        self.add(self)
+    }
+
+    #[inline(always)]
+    pub fn triple(&self) -> Self {
+        self.double().add(self)
+    }
+
+    /// `mul_u64` with the constant known at compile time, so the common
+    /// small cases become additions.
+    #[inline(always)]
+    pub fn mul_by_small_const<const K: u64>(&self) -> Self {
+        match K {
+            0 => Self([0; 4]),
+            1 => *self,
+            2 => self.double(),
+            3 => self.triple(),
+            4 => self.double().double(),
+            _ => self.mul_u64(K),
+        }
+    }
+
+    /// Modular addition. Both inputs must be reduced; the sum cannot
+    /// overflow 256 bits because the Bn254 modulus is below 2^254.
+    #[inline(always)]
+    pub fn add(&self, rhs: &Self) -> Self {
+        let mut t = self.0;
+        add_with_carry(&mut t, &rhs.0);
+        Self::sub_mod_if_big(&mut t);
+        Self(t)
+    }
+
+    /// Modular subtraction. Both inputs must be reduced.
+    #[inline(always)]
+    pub fn sub(&self, rhs: &Self) -> Self {
+        let mut t = self.0;
+        if sub_with_borrow(&mut t, &rhs.0) {
+            add_with_carry(&mut t, &MODULUS);
+        }
+        Self(t)
+    }
+
+    #[inline(always)]
+    pub fn square(&self) -> Self {
+        self.mul_edmsm(self)
+    }
+
+    /// Additive inverse. `self` must be reduced.
+    #[inline(always)]
+    pub fn neg(&self) -> Self {
+        Self::ZERO.sub(self)
+    }
+
+    /// `self^exp` for a little-endian 256-bit exponent, by left-to-right
+    /// square-and-multiply. Variable time in `exp`.
+    pub fn pow(&self, exp: &[u64; 4]) -> Self {
+        field::pow(self, exp)
+    }
+
+    /// Multiplicative inverse via Fermat, `self^(r - 2)`; `None` for zero.
+    pub fn inverse(&self) -> Option<Self> {
+        field::FieldOps::inverse(self)
+    }
+}
+
+/// Checks the input contract shared by `mul`, `mul_fios` and `mul_cihs`:
+/// `a * b < r * 2^256`, i.e. the high half of the product is below r.
+#[inline(always)]
+fn debug_assert_product_in_range(a: &CIOS, b: &CIOS) {
+    if cfg!(debug_assertions) {
+        let wide = a.mul_wide(b);
+        assert!(
+            !bigint_greater_eq(&[wide[4], wide[5], wide[6], wide[7]], &MODULUS),
+            "product of {a:x?} and {b:x?} is not below r * 2^256"
+        );
+    }
+}
+
+/// Maps `[0, 2r)` to `[0, r)` with one conditional subtraction.
+#[inline(always)]
+pub(crate) fn reduce_once(mut t: [u64; N]) -> [u64; N] {
+    if bigint_greater_eq(&t, &MODULUS) {
+        let mut borrow = 0;
+        borrow = sbb(&mut t[0], MODULUS[0], borrow);
+        borrow = sbb(&mut t[1], MODULUS[1], borrow);
+        borrow = sbb(&mut t[2], MODULUS[2], borrow);
+        sbb(&mut t[3], MODULUS[3], borrow);
+    }
+    t
+}
+
+/// The EdMSM loop of `CIOS::mul_edmsm` without the final conditional
+/// subtraction. For inputs below 2r the result stays below 2r, which is what
+/// `lazy::LazyFr` relies on.
+#[unroll_for_loops]
+#[inline(always)]
+pub(crate) fn edmsm_unreduced(a: &[u64; N], b: &[u64; N]) -> [u64; N] {
+    debug_assert!(
+        !bigint_greater_eq(a, &TWO_MODULUS) && !bigint_greater_eq(b, &TWO_MODULUS),
+        "EdMSM operands must be below 2r"
+    );
+    let mut t: [u64; N+1] = [0u64; N+1];
+    for i in 0..4 {
+        let mut c: u64 = 0u64;
+        for j in 0..4 {
+            (t[j], c) = mac(t[j], a[j], b[i], c);
+        }
+        t[N] = c;
+
+        let m = t[0].wrapping_mul(INV);
+        (_, c) = macx(t[0], m, MODULUS[0]);
+
+        for j in 1..4 {
+            (t[j-1], c) = mac(t[j], m, MODULUS[j], c);
+        }
+        t[N-1] = t[N] + c;
+    }
+
+    t[0..N].try_into().unwrap()
 }
 
 
@@ -190,6 +616,53 @@ pub(crate) fn sbb(a: &mut u64, b: u64, borrow: u64) -> u64 {
 }
 
 
+/// 128 x 128 -> 256 bit schoolbook product.
+#[inline(always)]
+fn mul_2x2(a: &[u64; 2], b: &[u64; 2]) -> [u64; 4] {
+    let (t0, c) = mac(0, a[0], b[0], 0);
+    let (t1, t2) = mac(0, a[0], b[1], c);
+    let (t1, c) = mac(t1, a[1], b[0], 0);
+    let (t2, c) = mac(t2, a[1], b[1], c);
+    [t0, t1, t2, c]
+}
+
+/// 128-bit addition, returning the sum and the carry bit.
+#[inline(always)]
+fn add_2x2(a: &[u64; 2], b: &[u64; 2]) -> ([u64; 2], u64) {
+    let (s0, c) = adc(a[0], b[0], 0);
+    let (s1, c) = adc(a[1], b[1], c);
+    ([s0, s1], c)
+}
+
+/// Adds `carry` into `t[from..]`. The carry is rippled through every limb
+/// above `from` whether or not it has been absorbed, so the kernels using
+/// this do not branch on their operands.
+#[inline(always)]
+fn add_carry_at(t: &mut [u64; N+2], from: usize, mut carry: u64) {
+    for limb in t[from..].iter_mut() {
+        (*limb, carry) = adc(*limb, carry, 0);
+    }
+}
+
+#[inline(always)]
+fn limbs_to_bytes(limbs: &[u64; 4]) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    for (chunk, limb) in bytes.chunks_exact_mut(8).zip(limbs) {
+        chunk.copy_from_slice(&limb.to_le_bytes());
+    }
+    bytes
+}
+
+#[inline(always)]
+fn add_with_carry(a: &mut [u64; 4], b: &[u64; 4]) -> bool {
+    let mut carry = 0;
+    (a[0], carry) = adc(a[0], b[0], carry);
+    (a[1], carry) = adc(a[1], b[1], carry);
+    (a[2], carry) = adc(a[2], b[2], carry);
+    (a[3], carry) = adc(a[3], b[3], carry);
+    carry != 0
+}
+
 #[inline(always)]
 fn sub_with_borrow(a: &mut [u64; 4], b: &[u64; 4]) -> bool {
     let mut borrow = 0;
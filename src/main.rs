@@ -1,18 +1,558 @@
 use ark_bn254::Fr as ArkFr;
-use halo2curves::{bn256::Fr as Halo2Fr, ff::Field};
-use fast_halo2curves::{bn256::Fr as NewHalo2Fr, ff::{Field as NewField, PrimeFieldBits}};
-use ark_std::UniformRand;
-use speedy_fields::CIOS;
-use std::{time::Instant, hint::black_box};
-use ark_std::rand::Rng;
-use ark_ff::biginteger::BigInt;
+use halo2curves::bn256::Fr as Halo2Fr;
+use fast_halo2curves::bn256::Fr as NewHalo2Fr;
+use speedy_fields::{
+    backend::{Cihs, Dispatched, EdMsm, FieldBackend, Fios, Karatsuba, Sos},
+    barrett::Barrett,
+    field::FieldOps,
+    report::{data_cache_bytes, HostInfo, PerfCounts, Record, Report},
+    CIOS,
+};
+#[cfg(all(feature = "perf", target_os = "linux"))]
+use speedy_fields::perf::{CounterValues, Counters};
+use std::{fs, mem::size_of, path::{Path, PathBuf}, thread, time::Instant, hint::black_box};
+use ark_std::rand::{rngs::StdRng, Rng, SeedableRng};
+use clap::{builder::PossibleValuesParser, Parser, Subcommand, ValueEnum};
 
-const SIZE: usize = 10_000_000;
+type Limbs = [u64; 4];
+type MeasureFn = fn(Op, Mode, &[(Limbs, Limbs)], &Args) -> Record;
+type MeasureThreadsFn = fn(Op, usize, &[(Limbs, Limbs)], &Args) -> Vec<f64>;
+type MeasureWorkingSetFn = fn(Op, WorkingSet, &[(Limbs, Limbs)], &Args) -> Vec<f64>;
+
+/// Every backend the binary can time. Adding one is one line here plus its
+/// `FieldBackend` impl.
+const BACKENDS: &[Backend] = &[
+    Backend::of::<ArkFr>("ark"),
+    Backend::of::<CIOS>("cios"),
+    Backend::of::<EdMsm>("edmsm"),
+    Backend::of::<Karatsuba>("karatsuba"),
+    Backend::of::<Sos>("sos"),
+    Backend::of::<Fios>("fios"),
+    Backend::of::<Cihs>("cihs"),
+    Backend::of::<Dispatched>("dispatch"),
+    Backend::of::<Barrett>("barrett"),
+    Backend::of::<Halo2Fr>("halo2"),
+    Backend::of::<NewHalo2Fr>("halo2-fast"),
+];
+
+struct Backend {
+    id: &'static str,
+    name: &'static str,
+    measure: MeasureFn,
+    measure_threads: MeasureThreadsFn,
+    measure_working_set: MeasureWorkingSetFn,
+}
+
+impl Backend {
+    const fn of<F: FieldBackend>(id: &'static str) -> Self {
+        Self {
+            id,
+            name: F::NAME,
+            measure: measure::<F>,
+            measure_threads: measure_threads::<F>,
+            measure_working_set: measure_working_set::<F>,
+        }
+    }
+}
+
+/// Where the operands of a `sweep` step live.
+#[derive(Debug, Clone, Copy)]
+enum WorkingSet {
+    /// Four independent accumulators and one fixed operand; no loads.
+    Registers,
+    /// A prefix of the operand pairs of about this many bytes, swept
+    /// repeatedly.
+    Bytes(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Op {
+    Mul,
+    Square,
+    Add,
+    Inverse,
+    Sqrt,
+}
+
+impl Op {
+    fn name(self) -> &'static str {
+        match self {
+            Op::Mul => "mul",
+            Op::Square => "square",
+            Op::Add => "add",
+            Op::Inverse => "inverse",
+            Op::Sqrt => "sqrt",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Mode {
+    /// Independent operations over the operand pairs; the CPU can overlap them.
+    Throughput,
+    /// A dependent chain, `acc = acc.mul(x)`, bound by the operation's latency
+    /// as in exponentiation or Horner evaluation.
+    Latency,
+}
+
+impl Mode {
+    fn name(self) -> &'static str {
+        match self {
+            Mode::Throughput => "throughput",
+            Mode::Latency => "latency",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Aligned plain-text columns.
+    Table,
+    /// A GitHub-flavoured markdown table.
+    Markdown,
+    /// One JSON document with host metadata and every record.
+    Json,
+    /// One row per record, host metadata repeated on each row.
+    Csv,
+}
+
+/// Times Bn254 Fr arithmetic across arkworks, halo2curves and the kernels in
+/// this crate.
+///
+/// Flags that subcommands read are global, so they may follow the
+/// subcommand: `scaling --threads 1,2 --backends cios`. `--baseline` is not,
+/// since `compare` has a `--baseline` of its own.
+#[derive(Debug, Clone, Parser)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Backends to time, comma separated [default: all]
+    #[arg(long, global = true, value_delimiter = ',', value_parser = PossibleValuesParser::new(BACKENDS.iter().map(|b| b.id)))]
+    backends: Vec<String>,
+
+    /// Operations to time, comma separated. Unary operations use the first
+    /// element of each pair.
+    #[arg(long, global = true, value_delimiter = ',', default_value = "mul")]
+    ops: Vec<Op>,
+
+    /// Timing modes, comma separated.
+    #[arg(long, value_delimiter = ',', default_value = "throughput,latency")]
+    modes: Vec<Mode>,
+
+    /// Number of operand pairs.
+    #[arg(long, global = true, default_value_t = 10_000_000)]
+    count: usize,
+
+    /// Timed passes over the operands; the reported time is their mean.
+    #[arg(long, global = true, default_value_t = 1)]
+    reps: usize,
+
+    /// Untimed passes over the operands before the timed ones.
+    #[arg(long, global = true, default_value_t = 0)]
+    warmup: usize,
+
+    /// Seed for the operand generator.
+    #[arg(long, global = true, default_value_t = 0)]
+    seed: u64,
+
+    /// Backend the others are reported relative to [default: the first
+    /// selected backend]
+    #[arg(long, value_parser = PossibleValuesParser::new(BACKENDS.iter().map(|b| b.id)))]
+    baseline: Option<String>,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+
+    /// Also write the JSON report to this file, for a later `compare`.
+    /// Needs --reps 2 or more.
+    #[arg(long)]
+    save: Option<PathBuf>,
+
+    /// Also count cycles, instructions and branch misses over the timed
+    /// passes with perf_event_open.
+    #[cfg(all(feature = "perf", target_os = "linux"))]
+    #[arg(long, global = true)]
+    perf: bool,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+enum Command {
+    /// Reruns the backends, operations and modes of a saved report, with its
+    /// count and reps, and prints each one's change against it. Exits
+    /// non-zero on a significant regression.
+    Compare(CompareArgs),
+    /// Splits the operands across 1..N threads and reports aggregate
+    /// throughput for each backend and operation, with speedup and scaling
+    /// efficiency relative to the first thread count.
+    Scaling(ScalingArgs),
+    /// Times every backend with operands resident in registers, L1, L2, L3
+    /// and DRAM, `--count` operations per step, to separate arithmetic cost
+    /// from memory effects. Cache levels whose working set would not be
+    /// smaller than all `--count` operands are skipped with a note.
+    Sweep,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+struct ScalingArgs {
+    /// Thread counts, comma separated [default: 1 up to the available
+    /// parallelism]
+    #[arg(long, value_delimiter = ',')]
+    threads: Vec<usize>,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+struct CompareArgs {
+    /// Report written by `--save` or `--format json`.
+    #[arg(long)]
+    baseline: PathBuf,
+
+    /// Compare this saved report instead of a fresh run.
+    #[arg(long)]
+    current: Option<PathBuf>,
+
+    /// Slowdown, in percent, that counts as a regression.
+    #[arg(long, default_value_t = 5.0)]
+    threshold: f64,
+
+    /// Welch's t a slowdown needs to be significant. Both reports need
+    /// records of at least two passes (--reps 2), or t is undefined.
+    #[arg(long, default_value_t = 3.0)]
+    min_t: f64,
+}
+
+/// ns/op of each timed pass of `op` over `inputs`, converted into the
+/// backend's representation up front.
+fn measure<F: FieldBackend>(op: Op, mode: Mode, inputs: &[(Limbs, Limbs)], args: &Args) -> Record {
+    let pairs: Vec<(F, F)> = inputs.iter().map(|&(a, b)| (F::from_limbs(a), F::from_limbs(b))).collect();
+    let pass = |pairs: &[(F, F)]| match mode {
+        Mode::Throughput => run(op, pairs),
+        Mode::Latency => run_chain(op, pairs),
+    };
+    for _ in 0..args.warmup {
+        pass(&pairs);
+    }
+    let mut probe = PerfProbe::new(args);
+    let samples = (0..args.reps)
+        .map(|_| {
+            let start = Instant::now();
+            probe.run(|| pass(&pairs));
+            start.elapsed().as_secs_f64() * 1e9 / pairs.len() as f64
+        })
+        .collect();
+    let mut record = Record::new(F::NAME, op.name(), mode.name(), pairs.len(), samples);
+    record.counters = probe.per_op(pairs.len() * args.reps);
+    record
+}
+
+/// Like `measure` in throughput mode, but each pass splits the pairs into
+/// `threads` contiguous chunks and runs them on scoped threads. ns/op is
+/// wall time over all pairs, so it falls as threads are added.
+fn measure_threads<F: FieldBackend>(op: Op, threads: usize, inputs: &[(Limbs, Limbs)], args: &Args) -> Vec<f64> {
+    let pairs: Vec<(F, F)> = inputs.iter().map(|&(a, b)| (F::from_limbs(a), F::from_limbs(b))).collect();
+    let chunk = pairs.len().div_ceil(threads);
+    let pass = || {
+        thread::scope(|s| {
+            for part in pairs.chunks(chunk) {
+                s.spawn(move || run(op, part));
+            }
+        })
+    };
+    for _ in 0..args.warmup {
+        pass();
+    }
+    (0..args.reps)
+        .map(|_| {
+            let start = Instant::now();
+            pass();
+            start.elapsed().as_secs_f64() * 1e9 / pairs.len() as f64
+        })
+        .collect()
+}
+
+/// ns/op of each timed run of `args.count` throughput-mode operations with
+/// the operands confined to `set`.
+fn measure_working_set<F: FieldBackend>(op: Op, set: WorkingSet, inputs: &[(Limbs, Limbs)], args: &Args) -> Vec<f64> {
+    let len = match set {
+        WorkingSet::Registers => 4,
+        WorkingSet::Bytes(bytes) => bytes / size_of::<(F, F)>(),
+    };
+    let len = len.clamp(1, inputs.len());
+    let pairs: Vec<(F, F)> = inputs[..len].iter().map(|&(a, b)| (F::from_limbs(a), F::from_limbs(b))).collect();
+    let (passes, ops) = match set {
+        WorkingSet::Registers => ((args.count / 4).max(1), (args.count / 4).max(1) * 4),
+        WorkingSet::Bytes(_) => ((args.count / len).max(1), (args.count / len).max(1) * len),
+    };
+    let pass = || match set {
+        WorkingSet::Registers => run_registers(op, &pairs, passes),
+        WorkingSet::Bytes(_) => {
+            for _ in 0..passes {
+                run(op, &pairs);
+            }
+        }
+    };
+    for _ in 0..args.warmup {
+        pass();
+    }
+    (0..args.reps)
+        .map(|_| {
+            let start = Instant::now();
+            pass();
+            start.elapsed().as_secs_f64() * 1e9 / ops as f64
+        })
+        .collect()
+}
+
+/// `rounds` operations on each of four independent chains, whose state
+/// stays in registers; the second operand is fixed.
+#[inline(always)]
+fn run_registers<F: FieldBackend>(op: Op, pairs: &[(F, F)], rounds: usize) {
+    let mut lanes: [F; 4] = std::array::from_fn(|i| pairs[i % pairs.len()].0);
+    let x = black_box(pairs[0].1);
+    match op {
+        Op::Mul => {
+            for _ in 0..rounds {
+                for a in lanes.iter_mut() {
+                    *a = a.mul(&x);
+                }
+            }
+        }
+        Op::Square => {
+            for _ in 0..rounds {
+                for a in lanes.iter_mut() {
+                    *a = a.square();
+                }
+            }
+        }
+        Op::Add => {
+            for _ in 0..rounds {
+                for a in lanes.iter_mut() {
+                    *a = a.add(&x);
+                }
+            }
+        }
+        // As in `run_chain`.
+        Op::Inverse => {
+            for _ in 0..rounds {
+                for a in lanes.iter_mut() {
+                    *a = a.inverse().unwrap_or(x);
+                }
+            }
+        }
+        Op::Sqrt => {
+            for _ in 0..rounds {
+                for a in lanes.iter_mut() {
+                    *a = a.sqrt().unwrap_or_else(|| a.add(&x));
+                }
+            }
+        }
+    }
+    black_box(lanes);
+}
+
+/// Hardware counters over the timed passes when `--perf` is given.
+#[cfg(all(feature = "perf", target_os = "linux"))]
+struct PerfProbe {
+    counters: Option<Counters>,
+    totals: CounterValues,
+}
+
+#[cfg(all(feature = "perf", target_os = "linux"))]
+impl PerfProbe {
+    fn new(args: &Args) -> Self {
+        let counters = args.perf.then(|| Counters::open().unwrap_or_else(|e| panic!("--perf: perf_event_open failed: {e}")));
+        Self { counters, totals: CounterValues::default() }
+    }
+
+    fn run(&mut self, f: impl FnOnce()) {
+        match &mut self.counters {
+            Some(counters) => self.totals += counters.measure(f).expect("reading perf counters").1,
+            None => f(),
+        }
+    }
+
+    fn per_op(&self, ops: usize) -> Option<PerfCounts> {
+        self.counters.as_ref().map(|_| self.totals.per_op(ops))
+    }
+}
+
+/// Built without the `perf` feature: passes run uncounted.
+#[cfg(not(all(feature = "perf", target_os = "linux")))]
+struct PerfProbe;
+
+#[cfg(not(all(feature = "perf", target_os = "linux")))]
+impl PerfProbe {
+    fn new(_: &Args) -> Self {
+        Self
+    }
+
+    fn run(&mut self, f: impl FnOnce()) {
+        f()
+    }
+
+    fn per_op(&self, _: usize) -> Option<PerfCounts> {
+        None
+    }
+}
+
+/// One pass of `op`, with the dispatch hoisted out of the loop.
+#[inline(always)]
+fn run<F: FieldBackend>(op: Op, pairs: &[(F, F)]) {
+    match op {
+        Op::Mul => {
+            for (a, b) in pairs {
+                black_box(a.mul(&black_box(*b)));
+            }
+        }
+        Op::Square => {
+            for (a, _) in pairs {
+                black_box(black_box(a).square());
+            }
+        }
+        Op::Add => {
+            for (a, b) in pairs {
+                black_box(a.add(&black_box(*b)));
+            }
+        }
+        Op::Inverse => {
+            for (a, _) in pairs {
+                black_box(black_box(a).inverse());
+            }
+        }
+        Op::Sqrt => {
+            for (a, _) in pairs {
+                black_box(black_box(a).sqrt());
+            }
+        }
+    }
+}
+
+/// One pass of `op` where each result is an operand of the next, so only
+/// one operation is ever in flight.
+#[inline(always)]
+fn run_chain<F: FieldBackend>(op: Op, pairs: &[(F, F)]) {
+    let mut acc = pairs[0].1;
+    match op {
+        Op::Mul => {
+            for (a, _) in pairs {
+                acc = acc.mul(a);
+            }
+        }
+        Op::Square => {
+            for _ in pairs {
+                acc = acc.square();
+            }
+        }
+        Op::Add => {
+            for (a, _) in pairs {
+                acc = acc.add(a);
+            }
+        }
+        // acc only reaches zero if the starting operand is zero.
+        Op::Inverse => {
+            for (a, _) in pairs {
+                acc = acc.inverse().unwrap_or(*a);
+            }
+        }
+        // Non-residues step to acc + a, a residue about half the time.
+        Op::Sqrt => {
+            for (a, _) in pairs {
+                acc = acc.sqrt().unwrap_or_else(|| acc.add(a));
+            }
+        }
+    }
+    black_box(acc);
+}
+
+fn print_report(report: &Report, format: Format) {
+    let relative = format!("vs {}", report.baseline);
+    // Counter columns only when `--perf` collected them.
+    let perf = report.records.iter().any(|r| r.counters.is_some());
+    match format {
+        Format::Table => {
+            let counters_header = if perf {
+                format!(" {:>10} {:>10} {:>6} {:>9}", "cycles", "instrs", "IPC", "br-misses")
+            } else {
+                String::new()
+            };
+            println!(
+                "{:<8} {:<11} {:<16} {:>12} {:>12} {:>14}{counters_header}",
+                "op", "mode", "backend", "ns/op", "stddev", relative
+            );
+            for r in &report.records {
+                let counters = r.counters.map_or(String::new(), |c| {
+                    format!(" {:>10.1} {:>10.1} {:>6.2} {:>9.4}", c.cycles, c.instructions, c.ipc, c.branch_misses)
+                });
+                println!(
+                    "{:<8} {:<11} {:<16} {:>12.2} {:>12.2} {:>13.2}x{counters}",
+                    r.op, r.mode, r.backend, r.mean_ns, r.stddev_ns, r.ratio_to_baseline
+                );
+            }
+        }
+        Format::Markdown => {
+            let (counters_header, counters_align) =
+                if perf { (" cycles | instrs | IPC | br-misses |", "---:|---:|---:|---:|") } else { ("", "") };
+            println!("| op | mode | backend | ns/op | stddev | {relative} |{counters_header}");
+            println!("|---|---|---|---:|---:|---:|{counters_align}");
+            for r in &report.records {
+                let counters = r.counters.map_or(String::new(), |c| {
+                    format!(" {:.1} | {:.1} | {:.2} | {:.4} |", c.cycles, c.instructions, c.ipc, c.branch_misses)
+                });
+                println!(
+                    "| {} | {} | {} | {:.2} | {:.2} | {:.2}x |{counters}",
+                    r.op, r.mode, r.backend, r.mean_ns, r.stddev_ns, r.ratio_to_baseline
+                );
+            }
+        }
+        Format::Json => println!("{}", report.to_json()),
+        Format::Csv => print!("{}", report.to_csv()),
+    }
+}
+
+/// Multiply then serialise, as when hashing or absorbing into a transcript.
+fn print_serialise_comparison(inputs: &[(Limbs, Limbs)]) {
+    let cios_mul_vec: Vec<_> = inputs.iter().map(|&(a, b)| (CIOS::from_canonical(a), CIOS::from_canonical(b))).collect();
+    let cios_serialise_start = Instant::now();
+    for (a, b) in cios_mul_vec.iter() {
+        let c = a.mul_edmsm(&black_box(*b));
+        black_box(c.to_bytes());
+    }
+    let cios_serialise_duration = cios_serialise_start.elapsed();
+    let barrett_mul_vec: Vec<_> = cios_mul_vec.iter().map(|(a, b)| (Barrett::from_cios(a), Barrett::from_cios(b))).collect();
+    let barrett_serialise_start = Instant::now();
+    for (a, b) in barrett_mul_vec.iter() {
+        let c = a.mul(&black_box(*b));
+        black_box(c.to_bytes());
+    }
+    let barrett_serialise_duration = barrett_serialise_start.elapsed();
+    println!(
+        "Time per multiply + serialise: EDMSM {:?}, Barrett {:?} ({:.2}x EDMSM)",
+        cios_serialise_duration / inputs.len() as u32,
+        barrett_serialise_duration / inputs.len() as u32,
+        barrett_serialise_duration.as_secs_f64() / cios_serialise_duration.as_secs_f64(),
+    );
+}
 
 fn main() {
-    let mut rng = ark_std::test_rng();
+    let args = Args::parse();
+    assert!(args.count > 0 && args.reps > 0, "--count and --reps must be positive");
+    assert!(
+        args.save.is_none() || args.reps >= 2,
+        "--save needs --reps 2 or more: compare cannot test a single pass for significance"
+    );
+    match &args.command {
+        Some(Command::Compare(compare_args)) => compare(&args, compare_args),
+        Some(Command::Scaling(scaling_args)) => scaling(&args, scaling_args),
+        Some(Command::Sweep) => sweep(&args),
+        None => benchmark(&args),
+    }
+}
 
-    let rands: Vec<([u64; 4], [u64; 4])> = (0..SIZE).map(|_| {
+/// `count` operand pairs of uniformly random limbs, reduced by each backend.
+fn inputs(args: &Args) -> Vec<(Limbs, Limbs)> {
+    let mut rng = StdRng::seed_from_u64(args.seed);
+    (0..args.count).map(|_| {
         let mut arr1 = [0u64; 4];
         let mut arr2 = [0u64; 4];
         for i in 0..4 {
@@ -20,70 +560,284 @@ fn main() {
             arr2[i] = rng.gen();
         }
         (arr1, arr2)
-    }).collect();
-
-    let ark_fr_mul_vec: Vec<(ArkFr, ArkFr)> = rands.iter().map(|&(arr1, arr2)| {
-        let a = ArkFr::new(BigInt::new(arr1));
-        let b = ArkFr::new(BigInt::new(arr2));
-        (a, b)
-    }).collect();
-    let ark_fr_mul_start = Instant::now();
-    for (a, b) in ark_fr_mul_vec {
-        let c = black_box(a) * black_box(b);
-        black_box(c);
-    }
-    let ark_fr_mul_duration = ark_fr_mul_start.elapsed();
-    let avg_fr = ark_fr_mul_duration / SIZE as u32;
-    println!("Time per ArkFr multiplication: {:?}", avg_fr);
-
-    let cios_mul_vec: Vec<_> = rands.iter().map(|&(arr1, arr2)| {
-        let a = CIOS(arr1);
-        let b = CIOS(arr2);
-        (a, b)
-    }).collect();
-    let cios_mul_start = Instant::now();
-    for (a, b) in cios_mul_vec.clone() {
-        let c = a.mul(&black_box(b));
-        black_box(c);
-    }
-    let cios_mul_duration = cios_mul_start.elapsed();
-    let cios_mul_multiple = cios_mul_duration.as_secs_f64() / ark_fr_mul_duration.as_secs_f64();
-    println!("Time per CIOS multiplication: {:?} ({:.2}x ArkFr)", cios_mul_duration / SIZE as u32, cios_mul_multiple);
-
-    let cios_edmsm_mul_start = Instant::now();
-    for (a, b) in cios_mul_vec {
-        let c = a.mul_edmsm(&black_box(b));
-        black_box(c);
-    }
-    let cios_edmsm_mul_duration = cios_edmsm_mul_start.elapsed();
-    let cios_edmsm_mul_multiple = cios_edmsm_mul_duration.as_secs_f64() / ark_fr_mul_duration.as_secs_f64();
-    println!("Time per CIOS multiplication with EDMSM: {:?} ({:.2}x ArkFr)", cios_edmsm_mul_duration / SIZE as u32, cios_edmsm_mul_multiple);
-
-    let halo2_fr_mul_vec: Vec<_> = rands.iter().map(|&(arr1, arr2)| {
-        let a = Halo2Fr::from_raw(arr1);
-        let b = Halo2Fr::from_raw(arr2);
-        (a, b)
-    }).collect();
-    let halo2_fr_mul_start = Instant::now();
-    for (mut a, b) in halo2_fr_mul_vec {
-        let c = black_box(a) * black_box(b);
-        black_box(c);
-    }
-    let halo2_fr_mul_duration = halo2_fr_mul_start.elapsed();
-    let halo2_fr_mul_multiple = halo2_fr_mul_duration.as_secs_f64() / ark_fr_mul_duration.as_secs_f64();
-    println!("Time per OLD Halo2Fr multiplication: {:?} ({:.2}x ArkFr)", halo2_fr_mul_duration / SIZE as u32, halo2_fr_mul_multiple);
-
-    let halo2_fr_mul_vec: Vec<_> = rands.iter().map(|&(arr1, arr2)| {
-        let a = NewHalo2Fr::from_raw(arr1);
-        let b = NewHalo2Fr::from_raw(arr2);
-        (a, b)
-    }).collect();
-    let halo2_fr_mul_start = Instant::now();
-    for (mut a, b) in halo2_fr_mul_vec {
-        let c = black_box(a) * black_box(b);
-        black_box(c);
-    }
-    let halo2_fr_mul_duration = halo2_fr_mul_start.elapsed();
-    let halo2_fr_mul_multiple = halo2_fr_mul_duration.as_secs_f64() / ark_fr_mul_duration.as_secs_f64();
-    println!("Time per NEW Halo2Fr multiplication: {:?} ({:.2}x ArkFr)", halo2_fr_mul_duration / SIZE as u32, halo2_fr_mul_multiple);
-}
\ No newline at end of file
+    }).collect()
+}
+
+/// The selected backends, with the baseline first if it was not selected,
+/// and the baseline.
+fn timed_backends(args: &Args) -> (Vec<&'static Backend>, &'static Backend) {
+    let mut timed: Vec<&Backend> = if args.backends.is_empty() {
+        BACKENDS.iter().collect()
+    } else {
+        BACKENDS.iter().filter(|b| args.backends.iter().any(|id| id == b.id)).collect()
+    };
+    let baseline = match &args.baseline {
+        Some(id) => BACKENDS.iter().find(|b| b.id == id).unwrap(),
+        None => timed[0],
+    };
+    if !timed.iter().any(|b| b.id == baseline.id) {
+        timed.insert(0, baseline);
+    }
+    (timed, baseline)
+}
+
+fn measure_all(args: &Args, inputs: &[(Limbs, Limbs)], host: HostInfo) -> Report {
+    let (timed, baseline) = timed_backends(args);
+    let mut records = Vec::new();
+    for &op in &args.ops {
+        for &mode in &args.modes {
+            for backend in &timed {
+                records.push((backend.measure)(op, mode, inputs, args));
+            }
+        }
+    }
+    let mut report = Report::new(host, baseline.name, records);
+    report.seed = Some(args.seed);
+    report.warmup = Some(args.warmup);
+    report
+}
+
+fn benchmark(args: &Args) {
+    let rands = inputs(args);
+    let human = matches!(args.format, Format::Table | Format::Markdown);
+    let host = HostInfo::detect();
+    if human && timed_backends(args).0.iter().any(|b| b.id == "dispatch") {
+        println!("Dispatched kernel: {}", host.dispatch_kernel);
+    }
+    let report = measure_all(args, &rands, host);
+    print_report(&report, args.format);
+    if let Some(path) = &args.save {
+        fs::write(path, report.to_json()).unwrap_or_else(|e| panic!("writing {}: {e}", path.display()));
+    }
+    if !human {
+        return;
+    }
+
+    let mul_record = |name: &str, mode: Mode| {
+        report.records.iter().find(|r| r.op == Op::Mul.name() && r.mode == mode.name() && r.backend == name)
+    };
+    let mul_mean = |name: &str, mode: Mode| mul_record(name, mode).map(|r| r.mean_ns);
+    for mode in [Mode::Throughput, Mode::Latency] {
+        if let (Some(edmsm), Some(cios)) = (mul_record(EdMsm::NAME, mode), mul_record(CIOS::NAME, mode)) {
+            let instructions = match (edmsm.counters, cios.counters) {
+                (Some(e), Some(c)) => format!(" ({:+.1} instructions/op)", e.instructions - c.instructions),
+                _ => String::new(),
+            };
+            println!("EDMSM mul {} is {:.2}x CIOS{instructions}", mode.name(), edmsm.mean_ns / cios.mean_ns);
+        }
+    }
+    if let (Some(karatsuba), Some(edmsm)) = (mul_mean(Karatsuba::NAME, Mode::Throughput), mul_mean(EdMsm::NAME, Mode::Throughput)) {
+        let karatsuba_vs_edmsm = karatsuba / edmsm;
+        println!(
+            "Karatsuba is {:.2}x EDMSM ({} on {})",
+            karatsuba_vs_edmsm,
+            if karatsuba_vs_edmsm < 1.0 { "beats EDMSM" } else { "loses to EDMSM" },
+            std::env::consts::ARCH,
+        );
+    }
+    if mul_mean(EdMsm::NAME, Mode::Throughput).is_some() && mul_mean(Barrett::NAME, Mode::Throughput).is_some() {
+        print_serialise_comparison(&rands);
+    }
+}
+
+fn read_report(path: &Path) -> Report {
+    let json = fs::read_to_string(path).unwrap_or_else(|e| panic!("reading {}: {e}", path.display()));
+    Report::from_json(&json).unwrap_or_else(|e| panic!("parsing {}: {e}", path.display()))
+}
+
+/// `args` narrowed to what `saved` measured, so a rerun times the same
+/// backends, operations and modes with the same count, reps, seed and
+/// warmup.
+fn rerun_args(args: &Args, saved: &Report) -> Args {
+    let id_of = |name: &str| BACKENDS.iter().find(|b| b.name == name).map(|b| b.id.to_string());
+    let mut rerun = args.clone();
+    rerun.backends.clear();
+    rerun.ops.clear();
+    rerun.modes.clear();
+    for r in &saved.records {
+        match id_of(&r.backend) {
+            Some(id) if !rerun.backends.contains(&id) => rerun.backends.push(id),
+            Some(_) => {}
+            None => eprintln!("skipping unknown backend {:?}", r.backend),
+        }
+        let op = Op::from_str(&r.op, false).unwrap_or_else(|e| panic!("saved op {:?}: {e}", r.op));
+        if !rerun.ops.contains(&op) {
+            rerun.ops.push(op);
+        }
+        let mode = Mode::from_str(&r.mode, false).unwrap_or_else(|e| panic!("saved mode {:?}: {e}", r.mode));
+        if !rerun.modes.contains(&mode) {
+            rerun.modes.push(mode);
+        }
+        rerun.count = r.count;
+        rerun.reps = r.samples_ns.len();
+    }
+    rerun.baseline = id_of(&saved.baseline);
+    match (saved.seed, saved.warmup) {
+        (Some(seed), Some(warmup)) => (rerun.seed, rerun.warmup) = (seed, warmup),
+        _ => eprintln!(
+            "warning: the baseline does not record its seed and warmup; using --seed {} --warmup {}",
+            args.seed, args.warmup
+        ),
+    }
+    rerun
+}
+
+fn compare(args: &Args, compare: &CompareArgs) {
+    let baseline = read_report(&compare.baseline);
+    check_reps("baseline", &compare.baseline, &baseline);
+    let current = match &compare.current {
+        Some(path) => {
+            let current = read_report(path);
+            check_reps("current", path, &current);
+            current
+        }
+        None => {
+            let args = rerun_args(args, &baseline);
+            measure_all(&args, &inputs(&args), HostInfo::detect())
+        }
+    };
+    println!("baseline: {} on {}", baseline.host.git_commit, baseline.host.cpu_model);
+    println!("current:  {} on {}", current.host.git_commit, current.host.cpu_model);
+    if baseline.host.cpu_model != current.host.cpu_model {
+        eprintln!("warning: the runs were measured on different CPUs");
+    }
+
+    println!(
+        "{:<8} {:<11} {:<16} {:>12} {:>12} {:>9} {:>8}",
+        "op", "mode", "backend", "baseline ns", "current ns", "change", "t"
+    );
+    let mut regressions = 0;
+    for c in current.changes_since(&baseline) {
+        let verdict = if c.is_regression(compare.threshold, compare.min_t) {
+            regressions += 1;
+            "REGRESSED"
+        } else if c.is_improvement(compare.threshold, compare.min_t) {
+            "improved"
+        } else {
+            ""
+        };
+        let row = format!(
+            "{:<8} {:<11} {:<16} {:>12.2} {:>12.2} {:>+8.2}% {:>8.2} {verdict}",
+            c.op, c.mode, c.backend, c.baseline_ns, c.current_ns, c.percent, c.t
+        );
+        println!("{}", row.trim_end());
+    }
+    if regressions > 0 {
+        eprintln!(
+            "{regressions} regression(s) slower by more than {}% with t > {}",
+            compare.threshold, compare.min_t
+        );
+        std::process::exit(1);
+    }
+}
+
+/// Exits non-zero if a record of `report` has a single pass: its t would be
+/// 0, so no regression could ever be reported against it.
+fn check_reps(which: &str, path: &Path, report: &Report) {
+    if report.min_reps() < 2 {
+        eprintln!(
+            "error: the {which} report {} has records with fewer than 2 passes, so Welch's t is undefined and \
+             regressions cannot be detected; save it again with --reps 2 or more",
+            path.display()
+        );
+        std::process::exit(2);
+    }
+}
+
+fn scaling(args: &Args, scaling: &ScalingArgs) {
+    let threads = if scaling.threads.is_empty() {
+        let available = thread::available_parallelism().map_or(1, |n| n.get());
+        (1..=available).collect()
+    } else {
+        scaling.threads.clone()
+    };
+    assert!(threads.iter().all(|&n| n > 0), "--threads must be positive");
+    let rands = inputs(args);
+    let (timed, _) = timed_backends(args);
+
+    println!(
+        "{:<8} {:<16} {:>8} {:>12} {:>10} {:>9} {:>11}",
+        "op", "backend", "threads", "ns/op", "Mops/s", "speedup", "efficiency"
+    );
+    for &op in &args.ops {
+        for backend in &timed {
+            let mut first = None;
+            for &n in &threads {
+                let samples = (backend.measure_threads)(op, n, &rands, args);
+                let ns = samples.iter().sum::<f64>() / samples.len() as f64;
+                let (first_threads, first_ns) = *first.get_or_insert((n, ns));
+                let speedup = first_ns / ns;
+                println!(
+                    "{:<8} {:<16} {:>8} {:>12.2} {:>10.2} {:>8.2}x {:>10.0}%",
+                    op.name(),
+                    backend.name,
+                    n,
+                    ns,
+                    1e3 / ns,
+                    speedup,
+                    speedup * first_threads as f64 / n as f64 * 100.0
+                );
+            }
+        }
+    }
+}
+
+fn sweep(args: &Args) {
+    const FALLBACK_CACHE_BYTES: [usize; 3] = [32 << 10, 1 << 20, 32 << 20];
+    let rands = inputs(args);
+    let all_bytes = rands.len() * size_of::<(Limbs, Limbs)>();
+    let mut sets = vec![("registers", WorkingSet::Registers)];
+    for (level, name) in [(1, "L1"), (2, "L2"), (3, "L3")] {
+        // Half the cache leaves room for the stack, code and page tables.
+        let bytes = data_cache_bytes(level).unwrap_or(FALLBACK_CACHE_BYTES[level as usize - 1]) / 2;
+        if bytes < all_bytes {
+            sets.push((name, WorkingSet::Bytes(bytes)));
+        } else {
+            eprintln!(
+                "note: skipping {name}: its {} KiB working set is not smaller than the {} KiB of operands; raise --count",
+                bytes >> 10,
+                all_bytes >> 10
+            );
+        }
+    }
+    let l3_bytes = data_cache_bytes(3).unwrap_or(FALLBACK_CACHE_BYTES[2]);
+    if all_bytes < 4 * l3_bytes {
+        eprintln!(
+            "warning: {} MiB of operands is under 4x the {} MiB L3, so the DRAM step is partly cached; raise --count",
+            all_bytes >> 20,
+            l3_bytes >> 20
+        );
+    }
+    sets.push(("DRAM", WorkingSet::Bytes(all_bytes)));
+    let (timed, _) = timed_backends(args);
+
+    println!(
+        "{:<8} {:<16} {:<10} {:>12} {:>12} {:>13}",
+        "op", "backend", "level", "set KiB", "ns/op", "vs registers"
+    );
+    for &op in &args.ops {
+        for backend in &timed {
+            let mut registers_ns = f64::NAN;
+            for &(name, set) in &sets {
+                let samples = (backend.measure_working_set)(op, set, &rands, args);
+                let ns = samples.iter().sum::<f64>() / samples.len() as f64;
+                let kib = match set {
+                    WorkingSet::Registers => {
+                        registers_ns = ns;
+                        "-".to_string()
+                    }
+                    WorkingSet::Bytes(bytes) => (bytes >> 10).to_string(),
+                };
+                println!(
+                    "{:<8} {:<16} {:<10} {:>12} {:>12.2} {:>12.2}x",
+                    op.name(),
+                    backend.name,
+                    name,
+                    kib,
+                    ns,
+                    ns / registers_ns
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,147 @@
+/// Hardware performance counters through Linux `perf_event_open`, so a
+/// benchmark can report cycles, instructions and branch misses next to wall
+/// time. Behind the `perf` feature; needs `kernel.perf_event_paranoid` of 2
+/// or lower, since only user-space events are counted.
+use std::{
+    fs::File,
+    io::{self, Read},
+    os::fd::{AsRawFd, FromRawFd},
+};
+
+use crate::report::PerfCounts;
+
+const PERF_TYPE_HARDWARE: u32 = 0;
+const PERF_COUNT_HW_CPU_CYCLES: u64 = 0;
+const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+const PERF_COUNT_HW_BRANCH_MISSES: u64 = 5;
+
+const PERF_FORMAT_TOTAL_TIME_ENABLED: u64 = 1 << 0;
+const PERF_FORMAT_TOTAL_TIME_RUNNING: u64 = 1 << 1;
+const PERF_FORMAT_GROUP: u64 = 1 << 3;
+
+const FLAG_DISABLED: u64 = 1 << 0;
+const FLAG_EXCLUDE_KERNEL: u64 = 1 << 5;
+const FLAG_EXCLUDE_HV: u64 = 1 << 6;
+
+const PERF_EVENT_IOC_ENABLE: libc::c_ulong = 0x2400;
+const PERF_EVENT_IOC_DISABLE: libc::c_ulong = 0x2401;
+const PERF_EVENT_IOC_RESET: libc::c_ulong = 0x2403;
+const PERF_IOC_FLAG_GROUP: libc::c_ulong = 1;
+
+/// The first `PERF_ATTR_SIZE_VER1` bytes of `struct perf_event_attr`; the
+/// kernel zero-extends older layouts. `libc` does not define it.
+#[repr(C)]
+#[derive(Default)]
+struct PerfEventAttr {
+    type_: u32,
+    size: u32,
+    config: u64,
+    sample_period: u64,
+    sample_type: u64,
+    read_format: u64,
+    flags: u64,
+    wakeup_events: u32,
+    bp_type: u32,
+    config1: u64,
+    config2: u64,
+}
+
+/// Cycles, instructions and branch misses of the calling thread, scheduled
+/// as one group so the three are always counted over the same interval.
+pub struct Counters {
+    leader: File,
+    _members: [File; 2],
+}
+
+/// Raw totals over one `Counters::measure` call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CounterValues {
+    pub cycles: u64,
+    pub instructions: u64,
+    pub branch_misses: u64,
+}
+
+impl CounterValues {
+    /// Totals divided by the number of operations they covered.
+    pub fn per_op(&self, ops: usize) -> PerfCounts {
+        let ops = ops as f64;
+        PerfCounts {
+            cycles: self.cycles as f64 / ops,
+            instructions: self.instructions as f64 / ops,
+            ipc: self.instructions as f64 / self.cycles as f64,
+            branch_misses: self.branch_misses as f64 / ops,
+        }
+    }
+}
+
+impl std::ops::AddAssign for CounterValues {
+    fn add_assign(&mut self, rhs: Self) {
+        self.cycles += rhs.cycles;
+        self.instructions += rhs.instructions;
+        self.branch_misses += rhs.branch_misses;
+    }
+}
+
+impl Counters {
+    /// Fails if the kernel or hypervisor exposes no PMU, or the paranoia
+    /// level forbids unprivileged counting.
+    pub fn open() -> io::Result<Self> {
+        let leader = open_event(PERF_COUNT_HW_CPU_CYCLES, None)?;
+        let instructions = open_event(PERF_COUNT_HW_INSTRUCTIONS, Some(&leader))?;
+        let branch_misses = open_event(PERF_COUNT_HW_BRANCH_MISSES, Some(&leader))?;
+        Ok(Self { leader, _members: [instructions, branch_misses] })
+    }
+
+    /// Runs `f` with the counters enabled. Counts are scaled up if the
+    /// kernel multiplexed the group off the PMU for part of the run.
+    pub fn measure<R>(&mut self, f: impl FnOnce() -> R) -> io::Result<(R, CounterValues)> {
+        self.ioctl(PERF_EVENT_IOC_RESET)?;
+        self.ioctl(PERF_EVENT_IOC_ENABLE)?;
+        let result = f();
+        self.ioctl(PERF_EVENT_IOC_DISABLE)?;
+
+        // { nr, time_enabled, time_running, value[nr] }
+        let mut buf = [0u8; 8 * 6];
+        self.leader.read_exact(&mut buf)?;
+        let word = |i: usize| u64::from_ne_bytes(buf[8 * i..8 * i + 8].try_into().unwrap());
+        let (enabled, running) = (word(1), word(2));
+        let scale = |count: u64| {
+            if running == 0 || running == enabled {
+                count
+            } else {
+                (count as f64 * enabled as f64 / running as f64) as u64
+            }
+        };
+        let values = CounterValues { cycles: scale(word(3)), instructions: scale(word(4)), branch_misses: scale(word(5)) };
+        Ok((result, values))
+    }
+
+    fn ioctl(&self, request: libc::c_ulong) -> io::Result<()> {
+        // A valid perf event fd and a request that takes an integer.
+        if unsafe { libc::ioctl(self.leader.as_raw_fd(), request as _, PERF_IOC_FLAG_GROUP) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+fn open_event(config: u64, leader: Option<&File>) -> io::Result<File> {
+    let attr = PerfEventAttr {
+        type_: PERF_TYPE_HARDWARE,
+        size: std::mem::size_of::<PerfEventAttr>() as u32,
+        config,
+        read_format: PERF_FORMAT_GROUP | PERF_FORMAT_TOTAL_TIME_ENABLED | PERF_FORMAT_TOTAL_TIME_RUNNING,
+        // Members follow the leader, which starts disabled.
+        flags: FLAG_EXCLUDE_KERNEL | FLAG_EXCLUDE_HV | if leader.is_none() { FLAG_DISABLED } else { 0 },
+        ..Default::default()
+    };
+    let group_fd = leader.map_or(-1, |f| f.as_raw_fd());
+    // `attr` outlives the call and `size` matches its layout; pid 0
+    // and cpu -1 count this thread on any CPU.
+    let fd = unsafe { libc::syscall(libc::SYS_perf_event_open, &attr as *const PerfEventAttr, 0, -1, group_fd, 0 as libc::c_ulong) };
+    if fd == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    // The kernel just returned this fd and nothing else owns it.
+    Ok(unsafe { File::from_raw_fd(fd as i32) })
+}
@@ -0,0 +1,86 @@
+/// Shoup-style multiplication by a fixed Bn254 Fr constant, for NTT twiddles
+/// and MLE challenges that multiply many elements by the same value.
+///
+/// For a Montgomery-form `x = aR` and the canonical constant `w = c`,
+/// `x * w mod r = acR` is exactly `x.mul_edmsm(&c_mont)`. With the precomputed
+/// `w' = floor(w * 2^256 / r)`, the quotient `q = floor(x * w' / 2^256)` is at
+/// most one below `floor(x * w / r)`, so `x * w - q * r` lies in [0, 2r) and
+/// only the low 256 bits of the two products are needed to compute it.
+use crate::{bigint_greater_eq, mac, sbb, sub_with_borrow, CIOS, MODULUS};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PreparedMul {
+    w: [u64; 4],
+    w_shoup: [u64; 4],
+}
+
+impl PreparedMul {
+    pub fn new(c: &CIOS) -> Self {
+        let w = c.to_canonical();
+        Self { w, w_shoup: shoup_quotient(&w) }
+    }
+
+    /// The constant, back in Montgomery form.
+    pub fn constant(&self) -> CIOS {
+        CIOS::from_canonical(self.w)
+    }
+
+    /// Same result as `x.mul_edmsm(&c)`. Any 256-bit `x` is accepted, and the
+    /// output is always reduced.
+    #[inline(always)]
+    pub fn mul(&self, x: &CIOS) -> CIOS {
+        let q = mul_high(&x.0, &self.w_shoup);
+        let mut res = mul_low(&x.0, &self.w);
+        let qr = mul_low(&q, &MODULUS);
+        let mut borrow = 0;
+        for (limb, s) in res.iter_mut().zip(qr) {
+            borrow = sbb(limb, s, borrow);
+        }
+        CIOS::sub_mod_if_big(&mut res);
+        CIOS(res)
+    }
+}
+
+/// Multiplies every element of `elems` by the prepared constant in place.
+pub fn scale_slice(elems: &mut [CIOS], c: &PreparedMul) {
+    for x in elems.iter_mut() {
+        *x = c.mul(x);
+    }
+}
+
+/// Low 256 bits of `a * b`.
+#[inline(always)]
+fn mul_low(a: &[u64; 4], b: &[u64; 4]) -> [u64; 4] {
+    let mut t = [0u64; 4];
+    for i in 0..4 {
+        let mut c = 0u64;
+        for j in 0..3 - i {
+            (t[i + j], c) = mac(t[i + j], a[i], b[j], c);
+        }
+        t[3] = t[3].wrapping_add(a[i].wrapping_mul(b[3 - i])).wrapping_add(c);
+    }
+    t
+}
+
+/// High 256 bits of `a * b`.
+#[inline(always)]
+fn mul_high(a: &[u64; 4], b: &[u64; 4]) -> [u64; 4] {
+    let wide = CIOS(*a).mul_wide(&CIOS(*b));
+    [wide[4], wide[5], wide[6], wide[7]]
+}
+
+/// `floor(w * 2^256 / r)` by restoring long division, one quotient bit per
+/// step. Only run once per constant.
+fn shoup_quotient(w: &[u64; 4]) -> [u64; 4] {
+    let mut rem = *w;
+    let mut quotient = [0u64; 4];
+    for bit in (0..256).rev() {
+        // rem < r < 2^254, so doubling cannot overflow
+        rem = [rem[0] << 1, (rem[1] << 1) | (rem[0] >> 63), (rem[2] << 1) | (rem[1] >> 63), (rem[3] << 1) | (rem[2] >> 63)];
+        if bigint_greater_eq(&rem, &MODULUS) {
+            sub_with_borrow(&mut rem, &MODULUS);
+            quotient[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+    quotient
+}
@@ -0,0 +1,345 @@
+/// Machine-readable benchmark results: one `Record` per (backend, operation,
+/// mode) and the `HostInfo` they were measured on, so runs from different
+/// machines can be aggregated without scraping the human-readable table.
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dispatch,
+    stats::{median, welch_t, RunningStats},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HostInfo {
+    pub cpu_model: String,
+    /// cpufreq scaling governor of cpu0, e.g. "performance" or "powersave".
+    pub governor: String,
+    pub rustc_version: String,
+    /// Features the binary was compiled with (`-C target-feature`/`target-cpu`).
+    pub target_features: Vec<String>,
+    /// Features the CPU reports at runtime.
+    pub cpu_features: Vec<String>,
+    /// `git describe --dirty` of the tree the binary was built from.
+    pub git_commit: String,
+    pub arch: String,
+    pub os: String,
+    pub dispatch_kernel: String,
+}
+
+impl HostInfo {
+    pub fn detect() -> Self {
+        Self {
+            cpu_model: cpu_model(),
+            governor: read_trimmed("/sys/devices/system/cpu/cpu0/cpufreq/scaling_governor"),
+            rustc_version: env!("SPEEDY_FIELDS_RUSTC_VERSION").to_string(),
+            target_features: target_features(),
+            cpu_features: cpu_features(),
+            git_commit: env!("SPEEDY_FIELDS_GIT_COMMIT").to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            os: std::env::consts::OS.to_string(),
+            dispatch_kernel: dispatch::selected().name().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    pub backend: String,
+    pub op: String,
+    /// "throughput" for independent operations, "latency" for a dependent
+    /// chain where each result feeds the next.
+    pub mode: String,
+    /// Operations per timed pass.
+    pub count: usize,
+    pub mean_ns: f64,
+    pub median_ns: f64,
+    /// Sample standard deviation across passes; zero for a single pass.
+    pub stddev_ns: f64,
+    /// `mean_ns` over the baseline backend's `mean_ns` for the same op and mode.
+    pub ratio_to_baseline: f64,
+    /// ns/op of every timed pass.
+    pub samples_ns: Vec<f64>,
+    /// Hardware counters over all timed passes, when collected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub counters: Option<PerfCounts>,
+}
+
+/// Per-operation hardware counter averages.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PerfCounts {
+    pub cycles: f64,
+    pub instructions: f64,
+    /// Instructions per cycle.
+    pub ipc: f64,
+    pub branch_misses: f64,
+}
+
+impl Record {
+    /// Summarises the per-pass ns/op samples. The ratio is filled in by
+    /// `Report::new`.
+    pub fn new(backend: &str, op: &str, mode: &str, count: usize, samples_ns: Vec<f64>) -> Self {
+        let stats = running_stats(&samples_ns);
+        Self {
+            backend: backend.to_string(),
+            op: op.to_string(),
+            mode: mode.to_string(),
+            count,
+            mean_ns: stats.mean(),
+            median_ns: median(&samples_ns),
+            stddev_ns: stats.stddev(),
+            ratio_to_baseline: f64::NAN,
+            samples_ns,
+            counters: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    pub host: HostInfo,
+    pub baseline: String,
+    /// Operand generator seed and untimed passes of the run, so `compare`
+    /// can reproduce it. `None` in reports saved before they were recorded.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub warmup: Option<usize>,
+    pub records: Vec<Record>,
+}
+
+/// CSV columns, in order. Host fields are repeated on every row so that
+/// files from several machines can simply be concatenated.
+const CSV_HEADER: [&str; 22] = [
+    "backend",
+    "op",
+    "mode",
+    "count",
+    "mean_ns",
+    "median_ns",
+    "stddev_ns",
+    "ratio_to_baseline",
+    "reps",
+    "cycles",
+    "instructions",
+    "ipc",
+    "branch_misses",
+    "baseline",
+    "cpu_model",
+    "governor",
+    "rustc_version",
+    "target_features",
+    "cpu_features",
+    "git_commit",
+    "arch",
+    "os",
+];
+
+impl Report {
+    /// Sets every record's ratio against the baseline's record for the same op
+    /// and mode.
+    pub fn new(host: HostInfo, baseline: &str, mut records: Vec<Record>) -> Self {
+        let baseline_means: Vec<(String, String, f64)> = records
+            .iter()
+            .filter(|r| r.backend == baseline)
+            .map(|r| (r.op.clone(), r.mode.clone(), r.mean_ns))
+            .collect();
+        for record in records.iter_mut() {
+            if let Some((_, _, mean)) =
+                baseline_means.iter().find(|(op, mode, _)| *op == record.op && *mode == record.mode)
+            {
+                record.ratio_to_baseline = record.mean_ns / mean;
+            }
+        }
+        Self { host, baseline: baseline.to_string(), seed: None, warmup: None, records }
+    }
+
+    /// The fewest timed passes behind any record. Welch's t needs at least
+    /// two on each side of a comparison.
+    pub fn min_reps(&self) -> usize {
+        self.records.iter().map(|r| r.samples_ns.len()).min().unwrap_or(0)
+    }
+
+    /// Every record that also appears in `baseline`, matched on backend, op
+    /// and mode.
+    pub fn changes_since(&self, baseline: &Report) -> Vec<Change> {
+        self.records
+            .iter()
+            .filter_map(|current| {
+                let old = baseline
+                    .records
+                    .iter()
+                    .find(|r| r.backend == current.backend && r.op == current.op && r.mode == current.mode)?;
+                Some(Change {
+                    backend: current.backend.clone(),
+                    op: current.op.clone(),
+                    mode: current.mode.clone(),
+                    baseline_ns: old.mean_ns,
+                    current_ns: current.mean_ns,
+                    percent: (current.mean_ns / old.mean_ns - 1.0) * 100.0,
+                    t: welch_t(&running_stats(&current.samples_ns), &running_stats(&old.samples_ns)),
+                })
+            })
+            .collect()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("reports always serialise")
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Header plus one row per record. Individual samples are only in JSON;
+    /// counter columns are empty when counters were not collected.
+    pub fn to_csv(&self) -> String {
+        let host = &self.host;
+        let mut csv = CSV_HEADER.join(",") + "\n";
+        for r in &self.records {
+            let row = [
+                r.backend.clone(),
+                r.op.clone(),
+                r.mode.clone(),
+                r.count.to_string(),
+                r.mean_ns.to_string(),
+                r.median_ns.to_string(),
+                r.stddev_ns.to_string(),
+                r.ratio_to_baseline.to_string(),
+                r.samples_ns.len().to_string(),
+                counter(r, |c| c.cycles),
+                counter(r, |c| c.instructions),
+                counter(r, |c| c.ipc),
+                counter(r, |c| c.branch_misses),
+                self.baseline.clone(),
+                host.cpu_model.clone(),
+                host.governor.clone(),
+                host.rustc_version.clone(),
+                host.target_features.join(" "),
+                host.cpu_features.join(" "),
+                host.git_commit.clone(),
+                host.arch.clone(),
+                host.os.clone(),
+            ];
+            csv += &row.iter().map(|field| csv_field(field)).collect::<Vec<_>>().join(",");
+            csv += "\n";
+        }
+        csv
+    }
+}
+
+/// One record's change between a saved baseline run and the current run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub backend: String,
+    pub op: String,
+    pub mode: String,
+    pub baseline_ns: f64,
+    pub current_ns: f64,
+    /// Change in mean ns/op; positive when the current run is slower.
+    pub percent: f64,
+    /// Welch's t over the per-pass samples, positive when slower. Zero when
+    /// neither run has spread, i.e. single-pass runs, which are therefore
+    /// never significant.
+    pub t: f64,
+}
+
+impl Change {
+    /// Slower by more than `threshold` percent, with |t| above `min_t`.
+    pub fn is_regression(&self, threshold: f64, min_t: f64) -> bool {
+        self.percent > threshold && self.t > min_t
+    }
+
+    /// Faster by more than `threshold` percent, with |t| above `min_t`.
+    pub fn is_improvement(&self, threshold: f64, min_t: f64) -> bool {
+        self.percent < -threshold && self.t < -min_t
+    }
+}
+
+fn running_stats(samples: &[f64]) -> RunningStats {
+    let mut stats = RunningStats::new();
+    for &x in samples {
+        stats.push(x);
+    }
+    stats
+}
+
+fn counter(record: &Record, field: impl Fn(&PerfCounts) -> f64) -> String {
+    record.counters.as_ref().map(|c| field(c).to_string()).unwrap_or_default()
+}
+
+/// Quotes a field if it contains a separator, quote or newline (RFC 4180).
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn read_trimmed(path: &str) -> String {
+    std::fs::read_to_string(path).map(|s| s.trim().to_string()).unwrap_or_else(|_| "unknown".to_string())
+}
+
+/// "model name" from /proc/cpuinfo on Linux.
+fn cpu_model() -> String {
+    std::fs::read_to_string("/proc/cpuinfo")
+        .ok()
+        .and_then(|info| {
+            info.lines()
+                .find(|line| line.starts_with("model name"))
+                .and_then(|line| line.split(':').nth(1))
+                .map(|model| model.trim().to_string())
+        })
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Size of cpu0's level-`level` data or unified cache, from sysfs on Linux.
+pub fn data_cache_bytes(level: u32) -> Option<usize> {
+    let caches = std::fs::read_dir("/sys/devices/system/cpu/cpu0/cache").ok()?;
+    caches.flatten().find_map(|index| {
+        let read = |file: &str| read_trimmed(&index.path().join(file).to_string_lossy());
+        if read("level") != level.to_string() || read("type") == "Instruction" {
+            return None;
+        }
+        let size = read("size");
+        let (digits, unit) = match size.strip_suffix('K') {
+            Some(kib) => (kib, 1 << 10),
+            None => (size.strip_suffix('M')?, 1 << 20),
+        };
+        digits.parse::<usize>().ok().map(|n| n * unit)
+    })
+}
+
+fn target_features() -> Vec<String> {
+    let compiled = [
+        ("adx", cfg!(target_feature = "adx")),
+        ("bmi2", cfg!(target_feature = "bmi2")),
+        ("avx2", cfg!(target_feature = "avx2")),
+        ("avx512f", cfg!(target_feature = "avx512f")),
+        ("avx512ifma", cfg!(target_feature = "avx512ifma")),
+        ("neon", cfg!(target_feature = "neon")),
+    ];
+    compiled.iter().filter(|(_, enabled)| *enabled).map(|(name, _)| name.to_string()).collect()
+}
+
+#[cfg(target_arch = "x86_64")]
+fn cpu_features() -> Vec<String> {
+    let detected = [
+        ("adx", is_x86_feature_detected!("adx")),
+        ("bmi2", is_x86_feature_detected!("bmi2")),
+        ("avx2", is_x86_feature_detected!("avx2")),
+        ("avx512f", is_x86_feature_detected!("avx512f")),
+        ("avx512ifma", is_x86_feature_detected!("avx512ifma")),
+    ];
+    detected.iter().filter(|(_, present)| *present).map(|(name, _)| name.to_string()).collect()
+}
+
+#[cfg(target_arch = "aarch64")]
+fn cpu_features() -> Vec<String> {
+    let detected = [("neon", std::arch::is_aarch64_feature_detected!("neon"))];
+    detected.iter().filter(|(_, present)| *present).map(|(name, _)| name.to_string()).collect()
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn cpu_features() -> Vec<String> {
+    Vec::new()
+}
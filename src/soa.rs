@@ -0,0 +1,124 @@
+/// Structure-of-arrays storage for Bn254 Fr elements in Montgomery form.
+/// Limb `k` of every element lives in its own column so element-wise loops
+/// touch four contiguous `u64` streams instead of strided 32-byte structs,
+/// which gives LLVM a chance to auto-vectorise.
+use crate::{wide::WideAccumulator, CIOS};
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FrVec {
+    limbs: [Vec<u64>; 4],
+}
+
+impl FrVec {
+    pub fn zeros(len: usize) -> Self {
+        Self {
+            limbs: [vec![0; len], vec![0; len], vec![0; len], vec![0; len]],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.limbs[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The `k`-th limb of every element.
+    pub fn column(&self, k: usize) -> &[u64] {
+        &self.limbs[k]
+    }
+
+    #[inline(always)]
+    pub fn get(&self, i: usize) -> CIOS {
+        CIOS([self.limbs[0][i], self.limbs[1][i], self.limbs[2][i], self.limbs[3][i]])
+    }
+
+    #[inline(always)]
+    pub fn set(&mut self, i: usize, value: &CIOS) {
+        for (column, limb) in self.limbs.iter_mut().zip(value.0) {
+            column[i] = limb;
+        }
+    }
+
+    pub fn add(&self, rhs: &Self) -> Self {
+        self.zip_map(rhs, |a, b| a.add(b))
+    }
+
+    pub fn sub(&self, rhs: &Self) -> Self {
+        self.zip_map(rhs, |a, b| a.sub(b))
+    }
+
+    /// Element-wise Montgomery product via `CIOS::mul_edmsm`.
+    pub fn mul(&self, rhs: &Self) -> Self {
+        self.zip_map(rhs, |a, b| a.mul_edmsm(b))
+    }
+
+    /// Multiplies every element by `c`.
+    pub fn scale(&self, c: &CIOS) -> Self {
+        self.zip_map(self, |a, _| a.mul_edmsm(c))
+    }
+
+    /// `self[i] += a * x[i]`
+    pub fn axpy(&mut self, a: &CIOS, x: &Self) {
+        assert_eq!(self.len(), x.len(), "FrVec length mismatch");
+        for i in 0..self.len() {
+            let y = self.get(i).add(&x.get(i).mul_edmsm(a));
+            self.set(i, &y);
+        }
+    }
+
+    /// `sum_i self[i] * rhs[i]`, with a single reduction at the end.
+    pub fn inner_product(&self, rhs: &Self) -> CIOS {
+        assert_eq!(self.len(), rhs.len(), "FrVec length mismatch");
+        let mut acc = WideAccumulator::new();
+        for i in 0..self.len() {
+            acc.add_product(&self.get(i), &rhs.get(i));
+        }
+        acc.reduce()
+    }
+
+    /// Every column re-sliced to exactly `len` so the per-element bounds
+    /// checks in `zip_map` can be hoisted out of the loop.
+    #[inline(always)]
+    fn columns(&self, len: usize) -> [&[u64]; 4] {
+        [&self.limbs[0][..len], &self.limbs[1][..len], &self.limbs[2][..len], &self.limbs[3][..len]]
+    }
+
+    #[inline(always)]
+    fn zip_map(&self, rhs: &Self, f: impl Fn(&CIOS, &CIOS) -> CIOS) -> Self {
+        assert_eq!(self.len(), rhs.len(), "FrVec length mismatch");
+        let n = self.len();
+        let (a, b) = (self.columns(n), rhs.columns(n));
+        let mut out = Self::zeros(n);
+        let [o0, o1, o2, o3] = &mut out.limbs;
+        let (o0, o1, o2, o3) = (&mut o0[..n], &mut o1[..n], &mut o2[..n], &mut o3[..n]);
+        for i in 0..n {
+            let c = f(&CIOS([a[0][i], a[1][i], a[2][i], a[3][i]]), &CIOS([b[0][i], b[1][i], b[2][i], b[3][i]]));
+            (o0[i], o1[i], o2[i], o3[i]) = (c.0[0], c.0[1], c.0[2], c.0[3]);
+        }
+        out
+    }
+}
+
+impl From<&[CIOS]> for FrVec {
+    fn from(elems: &[CIOS]) -> Self {
+        let mut out = Self::zeros(elems.len());
+        for (i, elem) in elems.iter().enumerate() {
+            out.set(i, elem);
+        }
+        out
+    }
+}
+
+impl From<Vec<CIOS>> for FrVec {
+    fn from(elems: Vec<CIOS>) -> Self {
+        Self::from(elems.as_slice())
+    }
+}
+
+impl From<FrVec> for Vec<CIOS> {
+    fn from(v: FrVec) -> Self {
+        (0..v.len()).map(|i| v.get(i)).collect()
+    }
+}
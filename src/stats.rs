@@ -0,0 +1,69 @@
+/// Streaming mean and variance (Welford's algorithm), so timing loops can
+/// record millions of samples without storing them.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RunningStats {
+    n: u64,
+    mean: f64,
+    m2: f64,
+}
+
+impl RunningStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, x: f64) {
+        self.n += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.n as f64;
+        self.m2 += delta * (x - self.mean);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.n
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Unbiased sample variance.
+    pub fn variance(&self) -> f64 {
+        if self.n < 2 {
+            0.0
+        } else {
+            self.m2 / (self.n - 1) as f64
+        }
+    }
+
+    pub fn stddev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+}
+
+/// Welch's t-statistic for the difference in means of two samples with
+/// possibly unequal variances. Zero when either side has no spread to
+/// compare against.
+pub fn welch_t(a: &RunningStats, b: &RunningStats) -> f64 {
+    let se2 = a.variance() / a.count() as f64 + b.variance() / b.count() as f64;
+    if se2 == 0.0 || !se2.is_finite() {
+        return 0.0;
+    }
+    (a.mean() - b.mean()) / se2.sqrt()
+}
+
+/// Median of `samples`, averaging the two middle values for even lengths.
+/// NaN for an empty slice.
+pub fn median(samples: &[f64]) -> f64 {
+    if samples.is_empty() {
+        return f64::NAN;
+    }
+    let mut sorted = samples.to_vec();
+    sorted.sort_unstable_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
@@ -0,0 +1,66 @@
+/// Lazy-reduction accumulator for sums of 512-bit products.
+/// Each `CIOS::mul_wide` output is below r^2 < 2^508, so the eight limbs
+/// absorb at least 16 products before wrapping. Wraps are counted in
+/// `overflow` and folded back in by `reduce`, so the number of terms is
+/// effectively unbounded.
+use crate::{adc, bigint_greater_eq, sub_with_borrow, CIOS, MODULUS, R2};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WideAccumulator {
+    limbs: [u64; 8],
+    overflow: u64,
+}
+
+impl WideAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `a * b` without reducing.
+    #[inline(always)]
+    pub fn add_product(&mut self, a: &CIOS, b: &CIOS) {
+        self.add_wide(&a.mul_wide(b));
+    }
+
+    /// Adds an arbitrary 512-bit value, e.g. one produced by `CIOS::mul_wide`.
+    #[inline(always)]
+    pub fn add_wide(&mut self, wide: &[u64; 8]) {
+        let mut carry = 0;
+        for (limb, w) in self.limbs.iter_mut().zip(wide) {
+            (*limb, carry) = adc(*limb, *w, carry);
+        }
+        self.overflow += carry;
+    }
+
+    /// Number of times the accumulator has wrapped past 2^512.
+    pub fn overflow(&self) -> u64 {
+        self.overflow
+    }
+
+    /// Low 512 bits of the running sum.
+    pub fn limbs(&self) -> &[u64; 8] {
+        &self.limbs
+    }
+
+    /// Montgomery-reduces the whole sum, i.e. returns `sum * R^{-1} mod r`.
+    /// For a sum of products of Montgomery-form elements this is the
+    /// Montgomery form of the sum of the underlying products.
+    ///
+    /// Splitting the sum as `lo + hi * 2^256 + overflow * 2^512` gives
+    /// `redc(lo) + hi + overflow * R (mod r)`, each piece of which is in range
+    /// for a single reduction step.
+    pub fn reduce(&self) -> CIOS {
+        let lo = CIOS::redc([self.limbs[0], self.limbs[1], self.limbs[2], self.limbs[3], 0, 0, 0, 0]);
+
+        let mut hi = [self.limbs[4], self.limbs[5], self.limbs[6], self.limbs[7]];
+        // hi < 2^256 < 6r
+        while bigint_greater_eq(&hi, &MODULUS) {
+            sub_with_borrow(&mut hi, &MODULUS);
+        }
+
+        // overflow * R^2 * R^{-1} = overflow * R
+        let overflow = CIOS::redc(CIOS([self.overflow, 0, 0, 0]).mul_wide(&CIOS(R2)));
+
+        lo.add(&CIOS(hi)).add(&overflow)
+    }
+}
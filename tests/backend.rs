@@ -0,0 +1,61 @@
+use ark_bn254::Fr as ArkFr;
+use ark_std::rand::Rng;
+use fast_halo2curves::bn256::Fr as NewHalo2Fr;
+use halo2curves::bn256::Fr as Halo2Fr;
+use speedy_fields::{
+    backend::{batch_inverse, Cihs, Dispatched, EdMsm, FieldBackend, Fios, Karatsuba, Sos},
+    barrett::Barrett,
+    CIOS,
+};
+
+const ITERS: usize = 100;
+
+/// Subtraction, negation, inverse, batch inverse and square root, checked
+/// through the backend's own arithmetic.
+fn check_backend<F: FieldBackend>() {
+    let (zero, one) = (F::from_limbs([0; 4]), F::from_limbs([1, 0, 0, 0]));
+    assert_eq!(zero.inverse(), None, "{}: zero has no inverse", F::NAME);
+    assert_eq!(zero.sqrt(), Some(zero), "{}: sqrt(0)", F::NAME);
+    // 5 generates the multiplicative group, so it is a non-residue.
+    assert_eq!(F::from_limbs([5, 0, 0, 0]).sqrt(), None, "{}: sqrt(5)", F::NAME);
+
+    let mut rng = ark_std::test_rng();
+    let mut non_residues = 0;
+    let mut xs = vec![zero];
+    for _ in 0..ITERS {
+        let x = F::from_limbs([rng.gen(), rng.gen(), rng.gen(), rng.gen()]);
+        let y = F::from_limbs([rng.gen(), rng.gen(), rng.gen(), rng.gen()]);
+        assert_eq!(x.sub(&y).add(&y), x, "{}: x - y + y", F::NAME);
+        assert_eq!(x.neg().add(&x), zero, "{}: -x + x", F::NAME);
+        assert_eq!(x.neg(), zero.sub(&x), "{}: -x", F::NAME);
+        assert_eq!(x.mul(&x.inverse().unwrap()), one, "{}: x * x^-1", F::NAME);
+        xs.push(x);
+        let square = x.square();
+        let root = square.sqrt().unwrap_or_else(|| panic!("{}: sqrt of a square", F::NAME));
+        assert_eq!(root.square(), square, "{}: sqrt(x^2)^2", F::NAME);
+        match x.sqrt() {
+            Some(root) => assert_eq!(root.square(), x, "{}: sqrt(x)^2", F::NAME),
+            None => non_residues += 1,
+        }
+    }
+    assert!(non_residues > 0 && non_residues < ITERS, "{}: {non_residues} non-residues", F::NAME);
+
+    let expected: Vec<F> = xs.iter().map(|x| x.inverse().unwrap_or(zero)).collect();
+    batch_inverse(&mut xs);
+    assert_eq!(xs, expected, "{}: batch_inverse", F::NAME);
+}
+
+#[test]
+fn every_backend_subtracts_inverts_and_takes_square_roots() {
+    check_backend::<ArkFr>();
+    check_backend::<CIOS>();
+    check_backend::<EdMsm>();
+    check_backend::<Karatsuba>();
+    check_backend::<Sos>();
+    check_backend::<Fios>();
+    check_backend::<Cihs>();
+    check_backend::<Dispatched>();
+    check_backend::<Barrett>();
+    check_backend::<Halo2Fr>();
+    check_backend::<NewHalo2Fr>();
+}
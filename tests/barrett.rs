@@ -0,0 +1,23 @@
+use speedy_fields::barrett::Barrett;
+
+const ITERS: usize = 10_000;
+
+#[test]
+fn barrett_matches_montgomery() {
+    for _ in 0..ITERS {
+        let (a, b) = (Barrett::rand(), Barrett::rand());
+        let (ma, mb) = (a.to_cios(), b.to_cios());
+        assert_eq!(Barrett::from_cios(&ma), a);
+        assert_eq!(a.mul(&b), Barrett::from_cios(&ma.mul(&mb)));
+        assert_eq!(a.add(&b), Barrett::from_cios(&ma.add(&mb)));
+        assert_eq!(a.sub(&b), Barrett::from_cios(&ma.sub(&mb)));
+        assert_eq!(a.mul(&b).to_bytes(), ma.mul(&mb).to_bytes());
+    }
+}
+
+#[test]
+fn barrett_reduces_largest_product() {
+    let minus_one = Barrett([0; 4]).sub(&Barrett([1, 0, 0, 0]));
+    assert_eq!(minus_one.mul(&minus_one), Barrett([1, 0, 0, 0]));
+    assert_eq!(minus_one.add(&minus_one), minus_one.sub(&Barrett([1, 0, 0, 0])));
+}
@@ -0,0 +1,22 @@
+//! Helpers shared by the integration tests. Each test crate uses a subset.
+#![allow(dead_code)]
+
+use speedy_fields::{CIOS, MODULUS};
+
+/// Raw Montgomery limbs r - 1, the largest reduced value.
+pub fn modulus_minus_one() -> CIOS {
+    CIOS::new([MODULUS[0] - 1, MODULUS[1], MODULUS[2], MODULUS[3]]).unwrap()
+}
+
+/// `a + r`, the unreduced representative of a reduced `a` in [r, 2r).
+pub fn plus_modulus(a: &CIOS) -> CIOS {
+    let mut limbs = *a.limbs();
+    let mut carry = 0u64;
+    for (limb, r) in limbs.iter_mut().zip(MODULUS) {
+        let sum = *limb as u128 + r as u128 + carry as u128;
+        (*limb, carry) = (sum as u64, (sum >> 64) as u64);
+    }
+    // r < 2^254, so a + r < 2^255.
+    assert_eq!(carry, 0);
+    CIOS::from_raw_unchecked(limbs)
+}
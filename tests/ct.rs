@@ -0,0 +1,38 @@
+use speedy_fields::{ct::CtFr, CIOS};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+const ITERS: usize = 1_000;
+
+fn rand_ct() -> CtFr {
+    CtFr::new(*CIOS::rand().limbs()).unwrap()
+}
+
+#[test]
+fn ct_matches_variable_time_arithmetic() {
+    for _ in 0..ITERS {
+        let (a, b) = (rand_ct(), rand_ct());
+        let (va, vb) = (a.to_cios(), b.to_cios());
+        assert_eq!(a.mul(&b).to_cios(), va.mul_edmsm(&vb));
+        assert_eq!(a.add(&b).to_cios(), va.add(&vb));
+        assert_eq!(a.sub(&b).to_cios(), va.sub(&vb));
+        assert_eq!(a.neg().add(&a), CtFr::ZERO);
+    }
+}
+
+#[test]
+fn ct_invert() {
+    for _ in 0..ITERS / 10 {
+        let a = rand_ct();
+        assert_eq!(a.invert().unwrap().mul(&a), CtFr::ONE);
+    }
+    assert!(bool::from(CtFr::ZERO.invert().is_none()));
+}
+
+#[test]
+fn ct_select_and_eq() {
+    let (a, b) = (rand_ct(), rand_ct());
+    assert_eq!(CtFr::conditional_select(&a, &b, Choice::from(0)), a);
+    assert_eq!(CtFr::conditional_select(&a, &b, Choice::from(1)), b);
+    assert!(bool::from(a.ct_eq(&a)));
+    assert!(bool::from(CtFr::new([u64::MAX; 4]).is_none()));
+}
@@ -0,0 +1,91 @@
+use ark_bn254::Fr as ArkFr;
+use ark_ff::{BigInt, PrimeField as ArkPrimeField};
+use ark_std::UniformRand;
+use fast_halo2curves::bn256::Fr as NewHalo2Fr;
+use halo2curves::bn256::Fr as Halo2Fr;
+use speedy_fields::CIOS;
+
+const ITERS: usize = 10_000;
+
+fn bytes_to_limbs(bytes: &[u8]) -> [u64; 4] {
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+        *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+    limbs
+}
+
+/// Canonical `a * b mod r` from each implementation, in the same order as
+/// `NAMES`.
+const NAMES: [&str; 5] = ["CIOS::mul", "CIOS::mul_edmsm", "ark_bn254", "halo2curves", "fast_halo2curves"];
+
+fn products(a: [u64; 4], b: [u64; 4]) -> [[u64; 4]; 5] {
+    let (ca, cb) = (CIOS::from_canonical(a), CIOS::from_canonical(b));
+    let ark = ArkFr::from_bigint(BigInt(a)).unwrap() * ArkFr::from_bigint(BigInt(b)).unwrap();
+    let halo2 = Halo2Fr::from_raw(a) * Halo2Fr::from_raw(b);
+    let new_halo2 = NewHalo2Fr::from_raw(a) * NewHalo2Fr::from_raw(b);
+    [
+        ca.mul(&cb).to_canonical(),
+        ca.mul_edmsm(&cb).to_canonical(),
+        ark.into_bigint().0,
+        // The fork may pin a different `ff`, hence the fully qualified calls.
+        bytes_to_limbs(halo2curves::ff::PrimeField::to_repr(&halo2).as_ref()),
+        bytes_to_limbs(fast_halo2curves::ff::PrimeField::to_repr(&new_halo2).as_ref()),
+    ]
+}
+
+fn assert_all_agree(a: [u64; 4], b: [u64; 4]) {
+    let results = products(a, b);
+    for (name, result) in NAMES.iter().zip(&results).skip(1) {
+        assert_eq!(result, &results[0], "{name} disagrees with {} on {a:x?} * {b:x?}", NAMES[0]);
+    }
+}
+
+fn edge_cases() -> Vec<[u64; 4]> {
+    let mut minus_one = ArkFr::MODULUS.0;
+    minus_one[0] -= 1;
+    let top = ArkFr::MODULUS.0[3];
+    vec![
+        [0, 0, 0, 0],
+        [1, 0, 0, 0],
+        minus_one,
+        [u64::MAX, 0, 0, 0],
+        [u64::MAX, u64::MAX, 0, 0],
+        [u64::MAX, u64::MAX, u64::MAX, 0],
+        [u64::MAX, u64::MAX, u64::MAX, top - 1],
+        [0, 0, 0, top - 1],
+    ]
+}
+
+#[test]
+fn montgomery_form_matches_arkworks() {
+    let mut rng = ark_std::test_rng();
+    for _ in 0..ITERS {
+        let x = ArkFr::rand(&mut rng);
+        let cios = CIOS::from_canonical(x.into_bigint().0);
+        assert_eq!(cios.limbs(), &x.0 .0);
+        assert_eq!(cios.to_canonical(), x.into_bigint().0);
+    }
+}
+
+#[test]
+fn random_products_agree() {
+    let mut rng = ark_std::test_rng();
+    for _ in 0..ITERS {
+        let a = ArkFr::rand(&mut rng).into_bigint().0;
+        let b = ArkFr::rand(&mut rng).into_bigint().0;
+        assert_all_agree(a, b);
+    }
+}
+
+#[test]
+fn edge_case_products_agree() {
+    let mut rng = ark_std::test_rng();
+    let edges = edge_cases();
+    for &a in &edges {
+        for &b in &edges {
+            assert_all_agree(a, b);
+        }
+        assert_all_agree(a, ArkFr::rand(&mut rng).into_bigint().0);
+    }
+}
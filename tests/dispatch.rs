@@ -0,0 +1,91 @@
+mod common;
+
+use common::plus_modulus;
+use speedy_fields::{
+    dispatch::{self, Kernel, KERNEL_ENV_VAR},
+    CIOS,
+};
+
+const ITERS: usize = 10_000;
+
+#[test]
+fn from_name_ignores_case_and_whitespace() {
+    for kernel in Kernel::ALL {
+        assert_eq!(Kernel::from_name(kernel.name()), Some(kernel));
+        assert_eq!(Kernel::from_name(&format!(" {} ", kernel.name().to_uppercase())), Some(kernel));
+    }
+    assert_eq!(Kernel::from_name("ifma"), None);
+    assert_eq!(Kernel::from_name(""), None);
+}
+
+#[test]
+fn portable_kernels_are_always_supported() {
+    assert!(Kernel::Cios.is_supported());
+    assert!(Kernel::EdMsm.is_supported());
+    assert!(Kernel::detect().is_supported());
+    #[cfg(target_arch = "x86_64")]
+    assert_eq!(
+        Kernel::Adx.is_supported(),
+        is_x86_feature_detected!("adx") && is_x86_feature_detected!("bmi2")
+    );
+    #[cfg(not(target_arch = "x86_64"))]
+    assert!(!Kernel::Adx.is_supported());
+}
+
+#[test]
+fn supported_kernels_agree_with_mul() {
+    let edge = [CIOS::ZERO, CIOS::ONE, CIOS::ZERO.sub(&CIOS::ONE)];
+    for kernel in Kernel::ALL.into_iter().filter(|k| k.is_supported()) {
+        for a in &edge {
+            for b in &edge {
+                assert_eq!(kernel.mul(a, b), a.mul(b), "{}({a:?}, {b:?})", kernel.name());
+            }
+        }
+        for _ in 0..ITERS {
+            let (a, b) = (CIOS::rand(), CIOS::rand());
+            assert_eq!(kernel.mul(&a, &b), a.mul(&b), "{}({a:?}, {b:?})", kernel.name());
+        }
+    }
+}
+
+#[test]
+fn edmsm_kernels_accept_operands_below_two_r() {
+    for kernel in [Kernel::EdMsm, Kernel::Adx].into_iter().filter(|k| k.is_supported()) {
+        for _ in 0..ITERS {
+            let (a, b) = (CIOS::rand(), CIOS::rand());
+            let expected = a.mul(&b);
+            assert_eq!(kernel.mul(&plus_modulus(&a), &b), expected, "{}", kernel.name());
+            assert_eq!(kernel.mul(&plus_modulus(&a), &plus_modulus(&b)), expected, "{}", kernel.name());
+        }
+    }
+}
+
+#[test]
+fn resolve_defaults_to_detect_and_honours_names() {
+    assert_eq!(dispatch::resolve(None), Kernel::detect());
+    assert_eq!(dispatch::resolve(Some("CIOS")), Kernel::Cios);
+    assert_eq!(dispatch::resolve(Some("edmsm")), Kernel::EdMsm);
+}
+
+#[test]
+#[should_panic(expected = "is not one of")]
+fn resolve_rejects_unknown_kernels() {
+    dispatch::resolve(Some("ifma"));
+}
+
+#[test]
+#[cfg(not(target_arch = "x86_64"))]
+#[should_panic(expected = "is not supported on this CPU")]
+fn resolve_rejects_unsupported_kernels() {
+    dispatch::resolve(Some("adx"));
+}
+
+/// The only test in this file that touches the cached selection, so setting
+/// the variable here happens before its first use.
+#[test]
+fn env_var_overrides_detection() {
+    std::env::set_var(KERNEL_ENV_VAR, "cios");
+    assert_eq!(dispatch::selected(), Kernel::Cios);
+    let (a, b) = (CIOS::rand(), CIOS::rand());
+    assert_eq!(dispatch::mul(&a, &b), a.mul(&b));
+}
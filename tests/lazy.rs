@@ -0,0 +1,36 @@
+mod common;
+
+use common::{modulus_minus_one, plus_modulus};
+use speedy_fields::{lazy::LazyFr, CIOS};
+
+const ITERS: usize = 10_000;
+
+/// The same element as `a`, but represented by `a + r`, to exercise the
+/// redundant upper half of the range.
+fn redundant(a: &CIOS) -> LazyFr {
+    LazyFr::new(*plus_modulus(a).limbs()).unwrap()
+}
+
+#[test]
+fn lazy_chain_matches_reduced_arithmetic() {
+    let mut acc = CIOS::rand();
+    let mut lazy_acc = redundant(&acc);
+    for _ in 0..ITERS {
+        let x = CIOS::rand();
+        let lazy_x = redundant(&x);
+        acc = acc.mul_edmsm(&x).add(&x).sub(&acc.mul(&acc));
+        lazy_acc = lazy_acc.mul(&lazy_x).add(&lazy_x).sub(&lazy_acc.square());
+        assert_eq!(lazy_acc.normalize(), acc);
+        assert_eq!(lazy_acc.to_bytes(), acc.to_bytes());
+    }
+}
+
+#[test]
+fn lazy_handles_top_of_range() {
+    let minus_one = modulus_minus_one();
+    let top = redundant(&minus_one);
+    assert_eq!(top.mul(&top).normalize(), minus_one.mul(&minus_one));
+    assert_eq!(top.add(&top).normalize(), minus_one.add(&minus_one));
+    assert_eq!(top.sub(&top).normalize(), CIOS::ZERO);
+    assert!(LazyFr::new([u64::MAX; 4]).is_none());
+}
@@ -0,0 +1,109 @@
+mod common;
+
+use common::modulus_minus_one;
+use speedy_fields::{
+    prepared::{scale_slice, PreparedMul},
+    FieldError, CIOS, MODULUS,
+};
+
+const ITERS: usize = 10_000;
+
+/// Raw limbs 0, 1 and r - 1.
+fn edge_cases() -> Vec<CIOS> {
+    vec![CIOS::ZERO, CIOS::new([1, 0, 0, 0]).unwrap(), modulus_minus_one()]
+}
+
+fn assert_variants_agree(a: &CIOS, b: &CIOS) {
+    let expected = a.mul(b);
+    assert_eq!(a.mul_edmsm(b), expected, "mul_edmsm({a:?}, {b:?})");
+    assert_eq!(a.mul_sos(b), expected, "mul_sos({a:?}, {b:?})");
+    assert_eq!(a.mul_fios(b), expected, "mul_fios({a:?}, {b:?})");
+    assert_eq!(a.mul_cihs(b), expected, "mul_cihs({a:?}, {b:?})");
+    assert_eq!(a.mul_karatsuba(b), expected, "mul_karatsuba({a:?}, {b:?})");
+}
+
+#[test]
+fn new_rejects_unreduced_limbs() {
+    let minus_one = *modulus_minus_one().limbs();
+    assert_eq!(CIOS::new(minus_one).map(|a| *a.limbs()), Ok(minus_one));
+    assert_eq!(CIOS::new(MODULUS), Err(FieldError::NotReduced(MODULUS)));
+    assert_eq!(CIOS::new([u64::MAX; 4]), Err(FieldError::NotReduced([u64::MAX; 4])));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "EdMSM operands must be below 2r")]
+fn mul_edmsm_rejects_operands_above_two_r() {
+    CIOS::from_raw_unchecked([u64::MAX; 4]).mul_edmsm(&CIOS::ONE);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "is not below r * 2^256")]
+fn mul_rejects_products_above_r_times_radix() {
+    let big = CIOS::from_raw_unchecked([u64::MAX; 4]);
+    big.mul(&big);
+}
+
+#[test]
+fn variants_agree_on_random_inputs() {
+    for _ in 0..ITERS {
+        assert_variants_agree(&CIOS::rand(), &CIOS::rand());
+    }
+}
+
+#[test]
+fn variants_agree_on_edge_cases() {
+    let edges = edge_cases();
+    for a in &edges {
+        for b in &edges {
+            assert_variants_agree(a, b);
+        }
+        assert_variants_agree(a, &CIOS::rand());
+    }
+}
+
+#[test]
+fn karatsuba_wide_product_matches_schoolbook() {
+    let all_ones = CIOS::from_raw_unchecked([u64::MAX; 4]);
+    assert_eq!(all_ones.mul_wide_karatsuba(&all_ones), all_ones.mul_wide(&all_ones));
+    for _ in 0..ITERS {
+        let (a, b) = (CIOS::rand(), CIOS::rand());
+        assert_eq!(a.mul_wide_karatsuba(&b), a.mul_wide(&b));
+    }
+}
+
+#[test]
+fn small_multiplication_matches_full_product() {
+    let mut inputs = edge_cases();
+    inputs.extend((0..ITERS).map(|_| CIOS::rand()));
+    for (i, a) in inputs.iter().enumerate() {
+        let k = if i % 2 == 0 { u64::MAX - i as u64 } else { CIOS::rand().limbs()[0] };
+        assert_eq!(a.mul_u64(k), a.mul(&CIOS::from_canonical([k, 0, 0, 0])), "{a:?} * {k}");
+        assert_eq!(a.mul_u32(k as u32), a.mul(&CIOS::from_canonical([k as u32 as u64, 0, 0, 0])));
+        assert_eq!(a.mul_by_small_const::<3>(), a.triple());
+        assert_eq!(a.mul_by_small_const::<5>(), a.mul(&CIOS::from_canonical([5, 0, 0, 0])));
+        assert_eq!(a.mul_by_small_const::<{ u64::MAX }>(), a.mul(&CIOS::from_canonical([u64::MAX, 0, 0, 0])));
+    }
+    // The largest t = a * k, where the quotient estimate is furthest off.
+    let minus_one = modulus_minus_one();
+    for k in [u64::MAX, u64::MAX - 1, 1 << 63] {
+        assert_eq!(minus_one.mul_u64(k), minus_one.mul(&CIOS::from_canonical([k, 0, 0, 0])), "{k}");
+    }
+}
+
+#[test]
+fn prepared_mul_matches_mul_edmsm() {
+    let mut constants = edge_cases();
+    constants.extend((0..16).map(|_| CIOS::rand()));
+    for c in &constants {
+        let prepared = PreparedMul::new(c);
+        assert_eq!(prepared.constant(), *c);
+        let elems: Vec<CIOS> = (0..ITERS / 16).map(|_| CIOS::rand()).chain(edge_cases()).collect();
+        let mut scaled = elems.clone();
+        scale_slice(&mut scaled, &prepared);
+        for (x, y) in elems.iter().zip(&scaled) {
+            assert_eq!(*y, x.mul_edmsm(c), "{x:?} * {c:?}");
+        }
+    }
+}
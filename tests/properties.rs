@@ -0,0 +1,114 @@
+use ark_bn254::Fr as ArkFr;
+use fast_halo2curves::bn256::Fr as NewHalo2Fr;
+use halo2curves::bn256::Fr as Halo2Fr;
+use proptest::prelude::*;
+use speedy_fields::{
+    backend::{Cihs, Dispatched, EdMsm, Fios, Karatsuba, Sos},
+    barrett::Barrett,
+    ct::CtFr,
+    field::FieldOps,
+    lazy::LazyFr,
+    CIOS, MODULUS,
+};
+
+fn geq(a: &[u64; 4], b: &[u64; 4]) -> bool {
+    a.iter().rev().cmp(b.iter().rev()) != std::cmp::Ordering::Less
+}
+
+/// Canonical integers below r. Shrinking acts on the raw limbs, so
+/// counterexamples shrink towards small values.
+fn canonical() -> impl Strategy<Value = [u64; 4]> {
+    prop_oneof![
+        any::<[u64; 4]>().prop_map(|mut a| {
+            a[3] &= (1 << 62) - 1;
+            while geq(&a, &MODULUS) {
+                let mut borrow = false;
+                for (limb, m) in a.iter_mut().zip(MODULUS) {
+                    let (d, b1) = limb.overflowing_sub(m);
+                    let (d, b2) = d.overflowing_sub(borrow as u64);
+                    (*limb, borrow) = (d, b1 | b2);
+                }
+            }
+            a
+        }),
+        Just([0; 4]),
+        Just([1, 0, 0, 0]),
+        Just([MODULUS[0] - 1, MODULUS[1], MODULUS[2], MODULUS[3]]),
+    ]
+}
+
+fn ring_axioms<F: FieldOps>(a: [u64; 4], b: [u64; 4], c: [u64; 4]) -> Result<(), TestCaseError> {
+    let (a, b, c) = (F::from_canonical(a), F::from_canonical(b), F::from_canonical(c));
+    prop_assert_eq!(a.add(&b).add(&c), a.add(&b.add(&c)), "{} add associativity", F::NAME);
+    prop_assert_eq!(a.mul(&b).mul(&c), a.mul(&b.mul(&c)), "{} mul associativity", F::NAME);
+    prop_assert_eq!(a.add(&b), b.add(&a), "{} add commutativity", F::NAME);
+    prop_assert_eq!(a.mul(&b), b.mul(&a), "{} mul commutativity", F::NAME);
+    prop_assert_eq!(a.mul(&b.add(&c)), a.mul(&b).add(&a.mul(&c)), "{} distributivity", F::NAME);
+    Ok(())
+}
+
+fn identities_and_inverses<F: FieldOps>(a: [u64; 4]) -> Result<(), TestCaseError> {
+    let x = F::from_canonical(a);
+    prop_assert_eq!(x.to_canonical(), a, "{} canonical round trip", F::NAME);
+    prop_assert_eq!(x.add(&F::zero()), x, "{} additive identity", F::NAME);
+    prop_assert_eq!(x.mul(&F::one()), x, "{} multiplicative identity", F::NAME);
+    prop_assert_eq!(x.mul(&F::zero()), F::zero(), "{} absorbing zero", F::NAME);
+    prop_assert_eq!(x.add(&x.neg()), F::zero(), "{} additive inverse", F::NAME);
+    prop_assert_eq!(x.sub(&x), F::zero(), "{} self subtraction", F::NAME);
+    prop_assert_eq!(x.square(), x.mul(&x), "{} square", F::NAME);
+    match x.inverse() {
+        Some(inv) => prop_assert_eq!(x.mul(&inv), F::one(), "{} multiplicative inverse", F::NAME),
+        None => prop_assert_eq!(x, F::zero(), "{} only zero has no inverse", F::NAME),
+    }
+    Ok(())
+}
+
+macro_rules! field_properties {
+    ($name:ident, $field:ty) => {
+        mod $name {
+            use super::*;
+
+            proptest! {
+                #[test]
+                fn ring_axioms(a in canonical(), b in canonical(), c in canonical()) {
+                    super::ring_axioms::<$field>(a, b, c)?;
+                }
+
+                #[test]
+                fn identities_and_inverses(a in canonical()) {
+                    super::identities_and_inverses::<$field>(a)?;
+                }
+            }
+        }
+    };
+}
+
+field_properties!(cios, CIOS);
+field_properties!(barrett, Barrett);
+field_properties!(lazy, LazyFr);
+field_properties!(ct, CtFr);
+field_properties!(edmsm, EdMsm);
+field_properties!(karatsuba, Karatsuba);
+field_properties!(sos, Sos);
+field_properties!(fios, Fios);
+field_properties!(cihs, Cihs);
+field_properties!(dispatch, Dispatched);
+field_properties!(ark, ArkFr);
+field_properties!(halo2, Halo2Fr);
+field_properties!(halo2_fast, NewHalo2Fr);
+
+proptest! {
+    /// Every multiplier variant agrees with `mul` on the whole reduced domain.
+    /// Unreduced limbs are outside the contract: both `mul` and `mul_edmsm`
+    /// can return limbs ≥ r there.
+    #[test]
+    fn mul_variants_agree(a in canonical(), b in canonical()) {
+        let (a, b) = (CIOS::from_canonical(a), CIOS::from_canonical(b));
+        let expected = a.mul(&b);
+        prop_assert_eq!(a.mul_edmsm(&b), expected);
+        prop_assert_eq!(a.mul_sos(&b), expected);
+        prop_assert_eq!(a.mul_fios(&b), expected);
+        prop_assert_eq!(a.mul_cihs(&b), expected);
+        prop_assert_eq!(a.mul_karatsuba(&b), expected);
+    }
+}
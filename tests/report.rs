@@ -0,0 +1,117 @@
+use speedy_fields::{
+    report::{HostInfo, PerfCounts, Record, Report},
+    stats::median,
+};
+
+fn host() -> HostInfo {
+    HostInfo {
+        cpu_model: "Test CPU, 2 \"cores\"".to_string(),
+        governor: "performance".to_string(),
+        rustc_version: "rustc 1.0.0".to_string(),
+        target_features: vec![],
+        cpu_features: vec!["adx".to_string(), "bmi2".to_string()],
+        git_commit: "abc123".to_string(),
+        arch: "x86_64".to_string(),
+        os: "linux".to_string(),
+        dispatch_kernel: "adx".to_string(),
+    }
+}
+
+fn report() -> Report {
+    let records = vec![
+        Record::new("ArkFr", "mul", "throughput", 100, vec![10.0, 12.0, 11.0]),
+        Record::new("CIOS", "mul", "throughput", 100, vec![5.0, 6.0, 7.0, 100.0]),
+        Record::new("ArkFr", "add", "throughput", 100, vec![2.0]),
+        Record::new("CIOS", "add", "throughput", 100, vec![3.0]),
+        Record::new("ArkFr", "mul", "latency", 100, vec![20.0]),
+        Record::new("CIOS", "mul", "latency", 100, vec![15.0]),
+    ];
+    Report::new(host(), "ArkFr", records)
+}
+
+#[test]
+fn records_summarise_samples() {
+    let r = report();
+    let cios_mul = &r.records[1];
+    assert_eq!(cios_mul.mean_ns, 29.5);
+    assert_eq!(cios_mul.median_ns, 6.5);
+    assert_eq!(r.records[2].stddev_ns, 0.0);
+    assert_eq!(median(&[3.0, 1.0, 2.0]), 2.0);
+    assert!(median(&[]).is_nan());
+}
+
+#[test]
+fn ratios_are_per_operation_and_mode() {
+    let ratios: Vec<f64> = report().records.iter().map(|r| r.ratio_to_baseline).collect();
+    assert_eq!(ratios, [1.0, 29.5 / 11.0, 1.0, 1.5, 1.0, 0.75]);
+}
+
+#[test]
+fn json_round_trips() {
+    let mut r = report();
+    assert_eq!(Report::from_json(&r.to_json()).unwrap(), r);
+    (r.seed, r.warmup) = (Some(7), Some(2));
+    assert_eq!(Report::from_json(&r.to_json()).unwrap(), r);
+}
+
+#[test]
+fn reports_without_seed_and_warmup_still_parse() {
+    let mut json: serde_json::Value = serde_json::from_str(&report().to_json()).unwrap();
+    let fields = json.as_object_mut().unwrap();
+    fields.remove("seed");
+    fields.remove("warmup");
+    let r = Report::from_json(&json.to_string()).unwrap();
+    assert_eq!((r.seed, r.warmup), (None, None));
+    assert_eq!(r.records, report().records);
+}
+
+#[test]
+fn min_reps_is_the_fewest_samples_of_any_record() {
+    let mut r = report();
+    assert_eq!(r.min_reps(), 1);
+    r.records.retain(|rec| rec.samples_ns.len() > 1);
+    assert_eq!(r.min_reps(), 3);
+    r.records.clear();
+    assert_eq!(r.min_reps(), 0);
+}
+
+#[test]
+fn csv_has_one_row_per_record_and_quotes_fields() {
+    let mut report = report();
+    report.records[1].counters = Some(PerfCounts { cycles: 100.0, instructions: 250.0, ipc: 2.5, branch_misses: 0.5 });
+    let csv = report.to_csv();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 7);
+    assert!(lines[0].starts_with("backend,op,mode,count,mean_ns,median_ns,stddev_ns,ratio_to_baseline"));
+    assert!(lines[1].starts_with("ArkFr,mul,throughput,100,11,11,1,1,3,,,,,ArkFr,\"Test CPU, 2 \"\"cores\"\"\",performance,"));
+    assert!(lines[1].ends_with(",adx bmi2,abc123,x86_64,linux"));
+    assert!(lines[2].contains(",4,100,250,2.5,0.5,ArkFr,"));
+}
+
+#[test]
+fn changes_match_records_and_need_significance() {
+    let baseline = report();
+    let mut records = baseline.records.clone();
+    // CIOS mul: 10% slower and consistently so; ArkFr add: 50% slower but
+    // a single pass, so t is zero.
+    records[1] = Record::new("CIOS", "mul", "throughput", 100, vec![5.5, 6.6, 7.7, 110.0]);
+    records[2] = Record::new("ArkFr", "add", "throughput", 100, vec![3.0]);
+    records.pop();
+    let current = Report::new(host(), "ArkFr", records);
+
+    let changes = current.changes_since(&baseline);
+    assert_eq!(changes.len(), 5);
+    let cios_mul = &changes[1];
+    assert_eq!((cios_mul.backend.as_str(), cios_mul.op.as_str()), ("CIOS", "mul"));
+    assert!((cios_mul.percent - 10.0).abs() < 1e-9);
+    assert!(cios_mul.t > 0.0);
+    assert!(cios_mul.is_regression(5.0, 0.0));
+    assert!(!cios_mul.is_regression(5.0, 1.0), "t = {}", cios_mul.t);
+    assert!(!cios_mul.is_regression(15.0, 0.0));
+
+    let ark_add = &changes[2];
+    assert_eq!(ark_add.percent, 50.0);
+    assert_eq!(ark_add.t, 0.0);
+    assert!(!ark_add.is_regression(5.0, 0.0));
+    assert!(!changes[0].is_improvement(0.0, 0.0));
+}
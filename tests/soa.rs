@@ -0,0 +1,109 @@
+use speedy_fields::{soa::FrVec, CIOS};
+
+const LEN: usize = 1_000;
+
+fn random_vec(len: usize) -> Vec<CIOS> {
+    (0..len).map(|_| CIOS::rand()).collect()
+}
+
+#[test]
+fn conversions_round_trip() {
+    let elems = random_vec(LEN);
+    let v = FrVec::from(elems.clone());
+    assert_eq!(v.len(), LEN);
+    assert_eq!(v, FrVec::from(elems.as_slice()));
+    for (i, elem) in elems.iter().enumerate() {
+        assert_eq!(v.get(i), *elem);
+        for k in 0..4 {
+            assert_eq!(v.column(k)[i], elem.limbs()[k]);
+        }
+    }
+    assert_eq!(Vec::<CIOS>::from(v), elems);
+
+    let empty = FrVec::from(Vec::new());
+    assert!(empty.is_empty());
+    assert_eq!(empty, FrVec::default());
+    assert!(Vec::<CIOS>::from(empty).is_empty());
+}
+
+#[test]
+fn set_overwrites_one_element() {
+    let mut v = FrVec::zeros(3);
+    let x = CIOS::rand();
+    v.set(1, &x);
+    assert_eq!(Vec::<CIOS>::from(v), vec![CIOS::ZERO, x, CIOS::ZERO]);
+}
+
+#[test]
+fn element_wise_ops_match_cios() {
+    let (a, b) = (random_vec(LEN), random_vec(LEN));
+    let (va, vb) = (FrVec::from(a.as_slice()), FrVec::from(b.as_slice()));
+    let c = CIOS::rand();
+
+    let add = Vec::<CIOS>::from(va.add(&vb));
+    let sub = Vec::<CIOS>::from(va.sub(&vb));
+    let mul = Vec::<CIOS>::from(va.mul(&vb));
+    let scale = Vec::<CIOS>::from(va.scale(&c));
+    for i in 0..LEN {
+        assert_eq!(add[i], a[i].add(&b[i]), "add at {i}");
+        assert_eq!(sub[i], a[i].sub(&b[i]), "sub at {i}");
+        assert_eq!(mul[i], a[i].mul(&b[i]), "mul at {i}");
+        assert_eq!(scale[i], a[i].mul(&c), "scale at {i}");
+    }
+}
+
+#[test]
+fn axpy_and_inner_product_match_cios() {
+    let (x, y) = (random_vec(LEN), random_vec(LEN));
+    let a = CIOS::rand();
+
+    let mut vy = FrVec::from(y.as_slice());
+    vy.axpy(&a, &FrVec::from(x.as_slice()));
+    let axpy = Vec::<CIOS>::from(vy);
+    for i in 0..LEN {
+        assert_eq!(axpy[i], y[i].add(&a.mul(&x[i])), "axpy at {i}");
+    }
+
+    let expected = x.iter().zip(&y).fold(CIOS::ZERO, |acc, (x, y)| acc.add(&x.mul(y)));
+    assert_eq!(FrVec::from(x.as_slice()).inner_product(&FrVec::from(y.as_slice())), expected);
+    assert_eq!(FrVec::zeros(0).inner_product(&FrVec::zeros(0)), CIOS::ZERO);
+}
+
+#[test]
+fn ops_handle_edge_elements() {
+    let minus_one = CIOS::ZERO.sub(&CIOS::ONE);
+    let edge = [CIOS::ZERO, CIOS::ONE, minus_one];
+    let pairs: Vec<(CIOS, CIOS)> = edge.iter().flat_map(|a| edge.iter().map(move |b| (*a, *b))).collect();
+    let va = FrVec::from(pairs.iter().map(|(a, _)| *a).collect::<Vec<_>>());
+    let vb = FrVec::from(pairs.iter().map(|(_, b)| *b).collect::<Vec<_>>());
+    let (add, sub, mul) = (Vec::<CIOS>::from(va.add(&vb)), Vec::<CIOS>::from(va.sub(&vb)), Vec::<CIOS>::from(va.mul(&vb)));
+    for (i, (a, b)) in pairs.iter().enumerate() {
+        assert_eq!(add[i], a.add(b));
+        assert_eq!(sub[i], a.sub(b));
+        assert_eq!(mul[i], a.mul(b));
+    }
+}
+
+#[test]
+#[should_panic(expected = "FrVec length mismatch")]
+fn add_rejects_mismatched_lengths() {
+    FrVec::zeros(3).add(&FrVec::zeros(4));
+}
+
+#[test]
+#[should_panic(expected = "FrVec length mismatch")]
+fn mul_rejects_mismatched_lengths() {
+    FrVec::zeros(4).mul(&FrVec::zeros(3));
+}
+
+#[test]
+#[should_panic(expected = "FrVec length mismatch")]
+fn axpy_rejects_mismatched_lengths() {
+    FrVec::zeros(3).axpy(&CIOS::ONE, &FrVec::zeros(2));
+}
+
+#[test]
+#[should_panic(expected = "FrVec length mismatch")]
+fn inner_product_rejects_mismatched_lengths() {
+    FrVec::zeros(2).inner_product(&FrVec::zeros(3));
+}
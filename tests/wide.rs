@@ -0,0 +1,83 @@
+mod common;
+
+use common::modulus_minus_one;
+use speedy_fields::{wide::WideAccumulator, CIOS};
+
+const ITERS: usize = 10_000;
+
+fn dot(terms: &[(CIOS, CIOS)]) -> CIOS {
+    terms.iter().fold(CIOS::ZERO, |acc, (a, b)| acc.add(&a.mul(b)))
+}
+
+fn accumulate(terms: &[(CIOS, CIOS)]) -> WideAccumulator {
+    let mut acc = WideAccumulator::new();
+    for (a, b) in terms {
+        acc.add_product(a, b);
+    }
+    acc
+}
+
+#[test]
+fn redc_of_wide_product_matches_mul() {
+    let edges = [CIOS::ZERO, CIOS::ONE, modulus_minus_one()];
+    for a in &edges {
+        for b in &edges {
+            assert_eq!(CIOS::redc(a.mul_wide(b)), a.mul(b), "{a:?} * {b:?}");
+        }
+    }
+    for _ in 0..ITERS {
+        let (a, b) = (CIOS::rand(), CIOS::rand());
+        assert_eq!(CIOS::redc(a.mul_wide(&b)), a.mul(&b));
+    }
+}
+
+#[test]
+fn empty_and_single_term_sums() {
+    let empty = WideAccumulator::new();
+    assert_eq!(empty.reduce(), CIOS::ZERO);
+    assert_eq!(empty.overflow(), 0);
+
+    for _ in 0..ITERS {
+        let (a, b) = (CIOS::rand(), CIOS::rand());
+        let acc = accumulate(&[(a, b)]);
+        assert_eq!(acc.limbs(), &a.mul_wide(&b));
+        assert_eq!(acc.reduce(), a.mul(&b));
+    }
+}
+
+#[test]
+fn dot_product_matches_sum_of_muls() {
+    for len in [2, 3, 16, 100] {
+        let terms: Vec<(CIOS, CIOS)> = (0..len).map(|_| (CIOS::rand(), CIOS::rand())).collect();
+        assert_eq!(accumulate(&terms).reduce(), dot(&terms), "{len} terms");
+    }
+}
+
+#[test]
+fn add_wide_matches_add_product() {
+    let (a, b) = (CIOS::rand(), CIOS::rand());
+    let mut wide = WideAccumulator::new();
+    wide.add_wide(&a.mul_wide(&b));
+    assert_eq!(wide, accumulate(&[(a, b)]));
+}
+
+/// 2^512 / (r - 1)^2 is about 27.99, so the eight limbs hold 27 such
+/// products and wrap on the 28th, after which `reduce` has to fold
+/// `overflow` back in through R^2.
+#[test]
+fn overflow_is_folded_back_in() {
+    let big = modulus_minus_one();
+    assert_eq!(accumulate(&[(big, big); 27]).overflow(), 0);
+    assert_eq!(accumulate(&[(big, big); 28]).overflow(), 1);
+    for len in [27, 28, 55, 56, 100, 1_000] {
+        let terms = vec![(big, big); len];
+        let acc = accumulate(&terms);
+        assert_eq!(acc.overflow(), (len / 28) as u64, "{len} terms");
+        assert_eq!(acc.reduce(), dot(&terms), "{len} terms");
+    }
+
+    let terms: Vec<(CIOS, CIOS)> = (0..1_000).map(|i| if i % 2 == 0 { (big, big) } else { (CIOS::rand(), big) }).collect();
+    let acc = accumulate(&terms);
+    assert!(acc.overflow() > 0);
+    assert_eq!(acc.reduce(), dot(&terms));
+}